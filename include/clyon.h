@@ -8,6 +8,11 @@ extern "C"
 	struct LyonPath;
 	struct LyonGeometry16;
 	struct LyonGeometry32;
+	struct LyonIntersections;
+	struct LyonPolylines;
+	struct LyonWireframe16;
+	struct LyonWireframe32;
+	struct LyonBlob;
 
 	struct LyonVector
 	{
@@ -38,6 +43,53 @@ extern "C"
 		uint32_t primitiveType;
 		uint32_t fillIndex;
 		uint32_t shapeIndex;
+
+		// Strokes only, zero for fills.
+		// Signed distance from the centerline, +width/2 on the positive side of the
+		// path and -width/2 on the negative side. Interpolated across the stroke this
+		// gives the exact distance to the centerline, for antialiasing and outlines.
+		float centerlineDistance;
+
+		// Strokes only, zero for fills.
+		// Distance along the path from the start of the sub-path, for dashes and
+		// marching ants. Each sub-path starts at 0.
+		float pathDistance;
+	};
+
+	// Elliptical radii for each corner of a rounded rectangle, like CSS `border-radius`.
+	// Top is the side with the smaller y coordinate.
+	struct LyonBorderRadii
+	{
+		LyonVector topLeft;
+		LyonVector topRight;
+		LyonVector bottomLeft;
+		LyonVector bottomRight;
+	};
+
+	enum LyonVerb
+	{
+		// Consumes one point, the start of the new sub-path.
+		LyonVerbMoveTo = 0,
+		// Consumes one point, the end of the line.
+		LyonVerbLineTo = 1,
+		// Consumes two points, the control point and the end of the curve.
+		LyonVerbQuadraticTo = 2,
+		// Consumes three points, both control points and the end of the curve.
+		LyonVerbCubicTo = 3,
+		// Consumes three points, as in LyonPathBuilder_Arc: the center, the radii as {rX, rY}
+		// and the angles as {sweepRadians, xRotation}.
+		LyonVerbArc = 4,
+		// Consumes no points.
+		LyonVerbClose = 5
+	};
+
+	// Sub-path orientation, based on the sign of its area. Counter-clockwise assumes the
+	// y axis points up, it appears clockwise on screen if the y axis points down.
+	enum LyonWinding
+	{
+		LyonWindingClockwise = -1,
+		LyonWindingNone = 0,
+		LyonWindingCounterClockwise = 1
 	};
 
 	struct LyonAABB
@@ -46,6 +98,84 @@ extern "C"
 		float upperRight[2];
 	};
 
+	// A rectangle rotated so that its first side is along `axis`, a unit vector.
+	// `halfExtents.x` is measured along `axis`, and `halfExtents.y` perpendicular to it.
+	struct LyonOrientedRect
+	{
+		LyonPoint center;
+		LyonVector axis;
+		LyonVector halfExtents;
+	};
+
+	struct LyonCircle
+	{
+		LyonPoint center;
+		float radius;
+	};
+
+	// Segments are numbered in the order of the path's events: lines, quadratic and cubic curves,
+	// and the closing edge of a closed sub-path when it is not empty. `t` is the curve parameter.
+	// A hit where two segments of a sub-path meet is reported once, at the start of the second.
+	struct LyonIntersection
+	{
+		LyonPoint position;
+		uint32_t segmentA;
+		float tA;
+		uint32_t segmentB;
+		float tB;
+	};
+
+	struct LyonGeometryStats
+	{
+		uint32_t vertexCount;
+		uint32_t indexCount;
+		uint32_t triangleCount;
+		// Triangles with a repeated index or no area.
+		uint32_t degenerateTriangleCount;
+		LyonAABB bounds;
+		// Sum of the areas of the triangles, overlapping ones are counted twice.
+		float totalArea;
+		// The memory allocated for the vertices and indices.
+		uint64_t memoryBytes;
+	};
+
+	struct LyonOptimizeStats
+	{
+		uint32_t verticesBefore;
+		uint32_t verticesAfter;
+		// Average cache miss ratio: misses per triangle in a 32 entry FIFO vertex cache.
+		float acmrBefore;
+		float acmrAfter;
+	};
+
+	struct LyonClosestPoint
+	{
+		LyonPoint position;
+		float distance;
+		// Distance along the path from its start, measured like LyonPath_Length.
+		float arcLength;
+		// Unit tangent, in the direction of the path.
+		LyonVector tangent;
+		// The segment and curve parameter, numbered like in LyonIntersection.
+		uint32_t segment;
+		float t;
+	};
+
+	// A 2D affine transform, laid out as a row-major 3x2 matrix applied to row vectors:
+	// x' = x * m11 + y * m21 + m31
+	// y' = x * m12 + y * m22 + m32
+	struct LyonTransform
+	{
+		float m11, m12;
+		float m21, m22;
+		float m31, m32;
+	};
+
+	inline LyonTransform LyonCreateIdentityTransform()
+	{
+		return LyonTransform{ 1, 0, 0, 1, 0, 0 };
+	}
+
 	enum LyonFillRule
 	{
 		LyonFillRuleEvenOdd = 0,
@@ -73,6 +203,17 @@ extern "C"
 		LyonLineJoinBevel = 3
 	};
 
+	// How the triangles are laid out in the geometry buffers.
+	enum LyonTopology
+	{
+		LyonTopologyIndexedTriangleList = 0,
+		// One vertex per triangle corner, and no indices.
+		LyonTopologyTriangleList = 1,
+		// Strips that keep the winding of the triangles, separated by the primitive restart
+		// index (0xFFFF or 0xFFFFFFFF), which no vertex uses.
+		LyonTopologyTriangleStrip = 2
+	};
+
 	struct LyonFillProperties
 	{
 		float tolerance;
@@ -82,6 +223,16 @@ extern "C"
 		uint32_t color;
 		int32_t fillIndex;
 		int32_t shapeIndex;
+
+		// When `clip` is non-zero, triangles are cut to `clipRect` on the CPU. When `clipPath`
+		// is not null, they are also cut to the area it fills with LyonFillRuleNonZero.
+		// New vertices interpolate the attributes of the triangle they were cut from.
+		int32_t clip;
+		LyonAABB clipRect;
+		LyonPath* clipPath;
+
+		// A LyonTopology.
+		int32_t topology;
 	};
 
 	struct LyonStrokeProperties
@@ -96,6 +247,46 @@ extern "C"
 		int32_t shapeIndex;
 		
 		float tolerance;
+
+		// When non-zero, outputs zero-width geometry along the centerline for
+		// screen-space constant width strokes.
+		//
+		// Each vertex `position` lies on the path, and `normal` is lyon's stroke
+		// normal: unit length along straight segments and caps, lengthened at miter
+		// joins, such that `position + normal * w / 2` is the vertex of a stroke of
+		// width `w`. Extrude by the desired width in the vertex shader.
+		//
+		// `centerlineDistance` is +-0.5, the distance for a unit width stroke.
+		// `width`, if positive, is the reference width used to generate the joins
+		// and caps, which otherwise assume a width of 1.
+		int32_t hairline;
+
+		// Same as in LyonFillProperties. Hairlines are clipped along their centerline, the
+		// extruded width can still reach outside of the clip region.
+		int32_t clip;
+		LyonAABB clipRect;
+		LyonPath* clipPath;
+
+		// A LyonTopology. LyonTessellateShape uses the topology of the fill for both parts.
+		int32_t topology;
+	};
+
+	enum LyonShapeOrder
+	{
+		LyonShapeOrderFillFirst = 0,
+		LyonShapeOrderStrokeFirst = 1
+	};
+
+	// Where each part of a combined fill and stroke tessellation lives in the index buffer.
+	// With LyonTopologyTriangleList there are no indices, and these are ranges of vertices.
+	// With LyonTopologyTriangleStrip the restart index between the two parts is the first
+	// index of the second part's range.
+	struct LyonShapeRanges
+	{
+		uint32_t fillFirstIndex;
+		uint32_t fillIndexCount;
+		uint32_t strokeFirstIndex;
+		uint32_t strokeIndexCount;
 	};
 
 	inline LyonPoint LyonCreatePoint(float x, float y)
@@ -143,6 +334,10 @@ extern "C"
 	/// Creates an empty path builder.
 	LyonPathBuilder*	LyonCreatePathBuilder				();
 
+	/// Creates a path builder that already contains all the sub-paths of the given path.
+	/// The path is not modified. The next command should start a new sub-path.
+	LyonPathBuilder*	LyonCreatePathBuilderFromPath		(LyonPath*);
+
 	/// Starts a new sub-path at the given position. 
 	/// Roughly correlates to the SVG command "M".
 	/// 
@@ -186,31 +381,283 @@ extern "C"
 	void 				LyonPathBuilder_AddRoundedRect(LyonPathBuilder*, LyonPoint min, LyonPoint max, float radius);
 	void				LyonPathBuilder_AddEllipse(LyonPathBuilder*, LyonPoint center, float rX, float rY, float xRotation);
 
+	// Rounded rectangles with a radius per corner. As with CSS, a corner with a zero radius is
+	// square, and radii that do not fit are all scaled down by the same factor.
+	void 				LyonPathBuilder_AddRoundedRectRadii(LyonPathBuilder*, LyonPoint min, LyonPoint max, float topLeft, float topRight, float bottomLeft, float bottomRight);
+	void 				LyonPathBuilder_AddRoundedRectElliptical(LyonPathBuilder*, LyonPoint min, LyonPoint max, LyonBorderRadii radii);
+
+	// Adds `count` points as a new sub-path, closed if `closed` is non-zero.
+	void				LyonPathBuilder_AddPolyline(LyonPathBuilder*, const LyonPoint* points, uint32_t count, int32_t closed);
+	void				LyonPathBuilder_AddPolygon(LyonPathBuilder*, const LyonPoint* points, uint32_t count);
+
+	// Same as LyonPathBuilder_AddPolyline, for points stored in an array of larger structures.
+	// Each point is two consecutive floats, and successive points are `stride` bytes apart. A
+	// `stride` of 0 means tightly packed points, as in LyonPathBuilder_AddPolyline. Nothing is
+	// added if `stride` is otherwise smaller than a LyonPoint.
+	void				LyonPathBuilder_AddPolylineStrided(LyonPathBuilder*, const void* data, uint32_t stride, uint32_t count, int32_t closed);
+
+	// Adds a whole path described by an array of LyonVerb (stored as bytes), and the points
+	// they consume, in order. The stream is validated first. If a verb is unknown or the verbs
+	// do not use exactly `pointCount` points, nothing is added and `error` is set. The error
+	// string must be free'd by calling LyonFreeString.
+	void				LyonPathBuilder_AddCommands(LyonPathBuilder*, const uint8_t* verbs, uint32_t verbCount, const LyonPoint* points, uint32_t pointCount, char * const * error);
+
+	// Angles are in radians from the x axis. Each shape is added as closed sub-paths.
+	// Polygons with fewer than 3 sides and stars with fewer than 2 branches add nothing.
+	void				LyonPathBuilder_AddRegularPolygon(LyonPathBuilder*, LyonPoint center, float radius, uint32_t sides, float rotation);
+	void				LyonPathBuilder_AddStar(LyonPathBuilder*, LyonPoint center, float outerRadius, float innerRadius, uint32_t branches, float rotation);
+	void				LyonPathBuilder_AddPie(LyonPathBuilder*, LyonPoint center, float radius, float startAngle, float sweepAngle);
+
+	// A ring segment between two radii. A sweep of 2 PI or more adds a full ring, with the inner
+	// circle wound in the opposite direction, and an inner radius of zero adds a pie.
+	void				LyonPathBuilder_AddAnnularSector(LyonPathBuilder*, LyonPoint center, float innerRadius, float outerRadius, float startAngle, float sweepAngle);
+
+	// A rectangle with semicircular ends around the segment between `from` and `to`.
+	void				LyonPathBuilder_AddCapsule(LyonPathBuilder*, LyonPoint from, LyonPoint to, float radius);
+
+	// An arrow pointing from `from` to `to`. The head length is clamped to the length of the arrow.
+	void				LyonPathBuilder_AddArrow(LyonPathBuilder*, LyonPoint from, LyonPoint to, float shaftWidth, float headWidth, float headLength);
+
 	// This function 'consumes' the PathBuilder, and frees it.
 	// Any additional access to the LyonPathBuilder after this function is invalid.
 	// Returns an error in the out paramter. The resulting string must be free'd by calling LyonFreeError
 	LyonPath*			LyonPathBuilder_Build				(LyonPathBuilder*, char * const * error);
 
 	// LyonPath functions
+
+	// The tight bounding box of the path, including the extrema of curves but not their
+	// control points.
 	LyonAABB			LyonPathBoundingRect	(LyonPath*);
 	void				LyonFreePath			(LyonPath*);
+
+	// Each of these returns a new path, which must be free'd by calling LyonFreePath.
+	LyonPath*			LyonPath_Clone			(LyonPath*);
+	LyonPath*			LyonPath_Concat			(LyonPath* first, LyonPath* second);
+
+	uint32_t			LyonPath_SubPathCount	(LyonPath*);
+
+	// Returns null if the index is out of range.
+	LyonPath*			LyonPath_ExtractSubPath	(LyonPath*, uint32_t index);
+
+	// Reverses the direction of every sub-path. The sub-paths also come out in reverse order.
+	LyonPath*			LyonPath_Reversed		(LyonPath*);
+
+	// Tolerances follow LyonFillProperties::tolerance, zero uses the default.
+	//
+	// Makes outer sub-paths counter-clockwise and holes clockwise, alternating with the
+	// nesting depth, so that the path fills the same with LyonFillRuleNonZero and
+	// LyonFillRuleEvenOdd. A sub-path is nested in another if most of its endpoints are inside it.
+	LyonPath*			LyonPath_NormalizeWinding	(LyonPath*, float tolerance);
+
+	// Positive for counter-clockwise sub-paths. Returns 0 if the index is out of range.
+	float				LyonPath_SubPathSignedArea	(LyonPath*, uint32_t index, float tolerance);
+
+	// Returns a LyonWinding.
+	int32_t				LyonPath_SubPathWinding		(LyonPath*, uint32_t index, float tolerance);
+
+	// Flattens the path, then removes every point closer than `epsilon` to the simplified
+	// polylines (Ramer-Douglas-Peucker). The result only contains straight lines.
+	LyonPath*			LyonPath_Simplify			(LyonPath*, float epsilon, float tolerance);
+
+	// Flattens the path, then fits as few cubic Béziers as possible through each sub-path,
+	// such that no flattened point is further than `maxError` from the curves.
+	LyonPath*			LyonPath_FitCurves			(LyonPath*, float maxError, float tolerance);
+
+	// Total length of all the sub-paths, including the closing edges.
+	float				LyonPath_Length				(LyonPath*, float tolerance);
+
+	// The part of the path between two fractions of its total length, like trim paths in
+	// animation tools. Curves are split exactly rather than flattened. Sub-paths that end
+	// within the range are kept, and every resulting sub-path is open.
+	LyonPath*			LyonPath_Trim				(LyonPath*, float start, float end, float tolerance);
+
+	// Same as LyonPath_Trim, with distances along the path instead of fractions.
+	LyonPath*			LyonPath_TrimDistance		(LyonPath*, float start, float end, float tolerance);
+
+	// Splits the path at the given distance along it. Both resulting paths must be free'd.
+	void				LyonPath_SplitAtDistance	(LyonPath*, float distance, float tolerance, LyonPath** first, LyonPath** second);
+
+	// The tight bounding box of the transformed path, rather than the transformed bounding box.
+	LyonAABB			LyonPath_TransformedBoundingRect	(LyonPath*, LyonTransform);
+
+	// The tight bounding box of a sub-path. Returns an empty box at the origin if the index is
+	// out of range.
+	LyonAABB			LyonPath_SubPathBoundingRect		(LyonPath*, uint32_t index);
+
+	// A conservative bounding box of the stroke, accounting for the width, joins (up to the miter
+	// limit) and caps. Hairlines are extruded on the GPU, so their box is the path's box.
+	LyonAABB			LyonPath_StrokeBoundingRect			(LyonPath*, LyonStrokeProperties);
+
+	// Approximates each sub-path with a polyline, with the same tolerance semantics as
+	// LyonFillProperties. The first point of a closed polyline is not repeated at its end.
+	LyonPolylines*		LyonPath_Flatten				(LyonPath*, float tolerance);
+
+	// Out of range indices give an empty polyline.
+	uint32_t			LyonPolylines_Count				(LyonPolylines*);
+	const LyonPoint*	LyonPolylines_PointsData		(LyonPolylines*, uint32_t index);
+	uint32_t			LyonPolylines_PointsLength		(LyonPolylines*, uint32_t index);
+	int32_t				LyonPolylines_IsClosed			(LyonPolylines*, uint32_t index);
+	void				LyonFreePolylines				(LyonPolylines*);
+
+	// Sum of the signed areas of the sub-paths, see LyonPath_SubPathSignedArea.
+	float				LyonPath_SignedArea			(LyonPath*, float tolerance);
+
+	// The area that is filled with the given LyonFillRule, and its centroid. The centroid of
+	// a path that fills nothing is the average of its points.
+	float				LyonPath_FilledArea			(LyonPath*, int32_t fillRule, float tolerance);
+	LyonPoint			LyonPath_Centroid			(LyonPath*, int32_t fillRule, float tolerance);
+
+	// The perimeter of the whole path is LyonPath_Length. Returns 0 if the index is out of range.
+	float				LyonPath_SubPathLength		(LyonPath*, uint32_t index, float tolerance);
+
+	// Whether the sub-path, considered closed, is a convex polygon once flattened.
+	int32_t				LyonPath_SubPathIsConvex	(LyonPath*, uint32_t index, float tolerance);
+
+	// Whether any two edges of the path cross, within a sub-path or between sub-paths.
+	// Edges that only touch at their endpoints, or overlap along a line, do not count.
+	int32_t				LyonPath_IsSelfIntersecting	(LyonPath*, float tolerance);
+
+	// The convex hull of the flattened path, as a single closed counter-clockwise polygon.
+	// It may be inside the curves by up to `tolerance`.
+	LyonPath*			LyonPath_ConvexHull				(LyonPath*, float tolerance);
+
+	// The smallest rectangle and circle containing the path. Both are grown by the tolerance
+	// so that they contain the curves, and not only the flattened path.
+	LyonOrientedRect	LyonPath_MinimumAreaRect		(LyonPath*, float tolerance);
+	LyonCircle			LyonPath_MinimumEnclosingCircle	(LyonPath*, float tolerance);
+
+	// Where the first path meets the second one, sorted along the first path.
+	LyonIntersections*	LyonPath_Intersections			(LyonPath* a, LyonPath* b);
+
+	// Where a ray meets the path, sorted from the origin. `segmentB` is 0 and `tB` is the distance
+	// along the ray in units of `direction`, so the first hit is the closest one.
+	LyonIntersections*	LyonPath_RayIntersections		(LyonPath*, LyonPoint origin, LyonVector direction);
+
+	// Where a line segment meets the path, sorted from `from`. `segmentB` is 0 and `tB` is the
+	// parameter on the line segment.
+	LyonIntersections*	LyonPath_SegmentIntersections	(LyonPath*, LyonPoint from, LyonPoint to);
+
+	const LyonIntersection*	LyonIntersections_Data		(LyonIntersections*);
+	uint32_t				LyonIntersections_Length	(LyonIntersections*);
+	void					LyonFreeIntersections		(LyonIntersections*);
+
+	// The point of the path that is closest to `point`. Returns 0 if the path is empty.
+	int32_t				LyonPath_ClosestPoint			(LyonPath*, LyonPoint point, float tolerance, LyonClosestPoint* result);
+
+	// Distance to the outline, negative inside the area filled with the given LyonFillRule.
+	// Returns FLT_MAX if the path is empty.
+	float				LyonPath_SignedDistance			(LyonPath*, LyonPoint point, int32_t fillRule, float tolerance);
 	LyonGeometry16*		LyonTessellateFill16	(LyonPath*, LyonFillProperties, char * const * error);
 	LyonGeometry16*		LyonTessellateStroke16	(LyonPath*, LyonStrokeProperties, char * const * error);
 	LyonGeometry32*		LyonTessellateFill32	(LyonPath*, LyonFillProperties, char * const * error);
 	LyonGeometry32*		LyonTessellateStroke32	(LyonPath*, LyonStrokeProperties, char * const * error);
 
+	// Tessellates both the fill and the stroke of the path into a single geometry, in the
+	// given LyonShapeOrder. The index ranges of each part are written to `ranges` if it is not null.
+	LyonGeometry16*		LyonTessellateShape16	(LyonPath*, LyonFillProperties, LyonStrokeProperties, int32_t order, LyonShapeRanges* ranges, char * const * error);
+	LyonGeometry32*		LyonTessellateShape32	(LyonPath*, LyonFillProperties, LyonStrokeProperties, int32_t order, LyonShapeRanges* ranges, char * const * error);
+
+	// Tessellates basic shapes without building a LyonPath. Filled rectangles and circles
+	// use lyon's dedicated routines for these shapes. The others are fed to the general path
+	// tessellator, like LyonPathBuilder_Add* followed by a LyonTessellate* call, only without
+	// the intermediate path. Negative radii are used as positive.
+	LyonGeometry16*		LyonTessellateFillRect16			(LyonPoint min, LyonPoint max, LyonFillProperties, char * const * error);
+	LyonGeometry16*		LyonTessellateFillRoundedRect16		(LyonPoint min, LyonPoint max, float radius, LyonFillProperties, char * const * error);
+	LyonGeometry16*		LyonTessellateFillCircle16			(LyonPoint center, float radius, LyonFillProperties, char * const * error);
+	LyonGeometry16*		LyonTessellateFillEllipse16			(LyonPoint center, float rX, float rY, float xRotation, LyonFillProperties, char * const * error);
+	LyonGeometry32*		LyonTessellateFillRect32			(LyonPoint min, LyonPoint max, LyonFillProperties, char * const * error);
+	LyonGeometry32*		LyonTessellateFillRoundedRect32		(LyonPoint min, LyonPoint max, float radius, LyonFillProperties, char * const * error);
+	LyonGeometry32*		LyonTessellateFillCircle32			(LyonPoint center, float radius, LyonFillProperties, char * const * error);
+	LyonGeometry32*		LyonTessellateFillEllipse32			(LyonPoint center, float rX, float rY, float xRotation, LyonFillProperties, char * const * error);
+
+	LyonGeometry16*		LyonTessellateStrokeRect16			(LyonPoint min, LyonPoint max, LyonStrokeProperties, char * const * error);
+	LyonGeometry16*		LyonTessellateStrokeRoundedRect16	(LyonPoint min, LyonPoint max, float radius, LyonStrokeProperties, char * const * error);
+	LyonGeometry16*		LyonTessellateStrokeCircle16		(LyonPoint center, float radius, LyonStrokeProperties, char * const * error);
+	LyonGeometry16*		LyonTessellateStrokeEllipse16		(LyonPoint center, float rX, float rY, float xRotation, LyonStrokeProperties, char * const * error);
+	LyonGeometry32*		LyonTessellateStrokeRect32			(LyonPoint min, LyonPoint max, LyonStrokeProperties, char * const * error);
+	LyonGeometry32*		LyonTessellateStrokeRoundedRect32	(LyonPoint min, LyonPoint max, float radius, LyonStrokeProperties, char * const * error);
+	LyonGeometry32*		LyonTessellateStrokeCircle32		(LyonPoint center, float radius, LyonStrokeProperties, char * const * error);
+	LyonGeometry32*		LyonTessellateStrokeEllipse32		(LyonPoint center, float rX, float rY, float xRotation, LyonStrokeProperties, char * const * error);
+
 	const LyonOutputVertex* LyonGeometry16_VerticesData		(LyonGeometry16*);
 	const uint16_t*			LyonGeometry16_IndicesData		(LyonGeometry16*);
 	uint32_t				LyonGeometry16_VerticesLength	(LyonGeometry16*);
 	uint32_t				LyonGeometry16_IndicesLength	(LyonGeometry16*);
+	// The LyonTopology the geometry was tessellated with, which tells how to read the indices.
+	int32_t					LyonGeometry16_Topology			(LyonGeometry16*);
 	void					LyonFreeGeometry16				(LyonGeometry16*);
 
 	const LyonOutputVertex* LyonGeometry32_VerticesData		(LyonGeometry32*);
 	const uint16_t*			LyonGeometry32_IndicesData		(LyonGeometry32*);
 	uint32_t				LyonGeometry32_VerticesLength	(LyonGeometry32*);
 	uint32_t				LyonGeometry32_IndicesLength	(LyonGeometry32*);
+	int32_t					LyonGeometry32_Topology			(LyonGeometry32*);
 	void					LyonFreeGeometry32				(LyonGeometry32*);
 
+	// Moves the vertices. `uv`, the untransformed position, and the distances along the path
+	// are kept in path units. Normals stay perpendicular to the outline and keep their length, so that the
+	// extrusion of hairlines is unchanged.
+	void					LyonGeometry16_Transform		(LyonGeometry16*, LyonTransform);
+	void					LyonGeometry32_Transform		(LyonGeometry32*, LyonTransform);
+
+	// Appends the vertices and indices of `src` to `dst`, rebasing the indices. Strips are
+	// separated by a restart index. Returns 0 without modifying `dst` if the indices would not
+	// fit, or if the topologies differ. An empty `dst` takes the topology of `src`. `dst` and
+	// `src` may be the same geometry, which is then doubled.
+	int32_t					LyonGeometry16_Append			(LyonGeometry16* dst, LyonGeometry16* src);
+	int32_t					LyonGeometry32_Append			(LyonGeometry32* dst, LyonGeometry32* src);
+
+	// Copies the geometry with another index size. LyonGeometry32_To16 returns null if the
+	// geometry has too many vertices for 16-bit indices.
+	LyonGeometry16*			LyonGeometry32_To16				(LyonGeometry32*);
+	LyonGeometry32*			LyonGeometry16_To32				(LyonGeometry16*);
+
+	// Welds vertices that are within `weldEpsilon` of each other in every attribute, and have the
+	// same color, type and indices, then removes the triangles that collapse. A negative epsilon
+	// skips welding. Then reorders the triangles for the post-transform vertex cache, and the
+	// vertices in the order they are first used. Unused vertices are removed.
+	// Returns 0 without modifying the geometry if it has no triangles, or if its topology is not
+	// LyonTopologyIndexedTriangleList. `stats` can be null.
+	int32_t					LyonGeometry16_Optimize			(LyonGeometry16*, float weldEpsilon, LyonOptimizeStats* stats);
+	int32_t					LyonGeometry32_Optimize			(LyonGeometry32*, float weldEpsilon, LyonOptimizeStats* stats);
+
+	// Triangles are read with the topology of the geometry, so strips count the triangles they
+	// draw. The bounds cover all the vertices, and are empty at the origin if there are none.
+	LyonGeometryStats		LyonGeometry16_Stats			(LyonGeometry16*);
+	LyonGeometryStats		LyonGeometry32_Stats			(LyonGeometry32*);
+
+	// Saves the geometry to a versioned binary blob: a header with the format version, index
+	// size, LyonTopology, vertex layout, counts and bounds, followed by the vertex and index
+	// data. All values are little-endian. Returns null if the blob would be 4 GiB or more, as
+	// its length is a uint32_t.
+	LyonBlob*				LyonGeometry16_Serialize		(LyonGeometry16*);
+	LyonBlob*				LyonGeometry32_Serialize		(LyonGeometry32*);
+	const uint8_t*			LyonBlob_Data					(LyonBlob*);
+	uint32_t				LyonBlob_Length					(LyonBlob*);
+	void					LyonFreeBlob					(LyonBlob*);
+
+	// Loads a blob saved with either index size, with the topology it was saved with. Returns
+	// null and sets `error` if the data is truncated, from another version or vertex layout, if
+	// the counts do not match the topology, or if the indices are out of range or do not fit.
+	LyonGeometry16*			LyonDeserializeGeometry16		(const uint8_t* data, uint32_t length, char * const * error);
+	LyonGeometry32*			LyonDeserializeGeometry32		(const uint8_t* data, uint32_t length, char * const * error);
+
+	// A line list with one pair of indices per edge of the triangles, for drawing wireframes
+	// over the geometry. Edges are matched by vertex position, so each one appears once.
+	// With `outlineOnly` non-zero, only the edges of a single triangle are kept: the outline
+	// of a fill. Strips are read by the topology of the geometry, and give the same edges as
+	// lists. Returns null if an unindexed geometry has more vertices than its indices can
+	// address.
+	LyonWireframe16*		LyonGeometry16_Wireframe		(LyonGeometry16*, int32_t outlineOnly);
+	const uint16_t*			LyonWireframe16_IndicesData		(LyonWireframe16*);
+	uint32_t				LyonWireframe16_IndicesLength	(LyonWireframe16*);
+	void					LyonFreeWireframe16				(LyonWireframe16*);
+
+	LyonWireframe32*		LyonGeometry32_Wireframe		(LyonGeometry32*, int32_t outlineOnly);
+	const uint32_t*			LyonWireframe32_IndicesData		(LyonWireframe32*);
+	uint32_t				LyonWireframe32_IndicesLength	(LyonWireframe32*);
+	void					LyonFreeWireframe32				(LyonWireframe32*);
+
 	uint32_t				LyonVersion();
 	void					LyonFreeString(const char*);
 
@@ -5,11 +5,11 @@ use lyon::{
     geom::{Arc, QuadraticBezierSegment},
     math::Angle,
     math::Point,
-    math::{point, vector},
+    math::{point, vector, Vector},
     path::{traits::SvgPathBuilder, Polygon},
 };
 
-use crate::types::{InternalBuilder, LyonPoint};
+use crate::types::{InternalBuilder, LyonBorderRadii, LyonPoint, LyonVector};
 
 pub fn add_rectangle(p: *mut InternalBuilder, min: LyonPoint, max: LyonPoint) {
     assert!(!p.is_null());
@@ -28,6 +28,19 @@ pub fn add_rectangle(p: *mut InternalBuilder, min: LyonPoint, max: LyonPoint) {
     builder.add_polygon(rect);
 }
 
+pub fn add_polyline(p: *mut InternalBuilder, points: &[Point], closed: bool) {
+    assert!(!p.is_null());
+
+    if points.is_empty() {
+        return;
+    }
+
+    let polyline = Polygon { points, closed };
+
+    let builder = unsafe { &mut (*p) };
+    builder.add_polygon(polyline);
+}
+
 pub fn add_ellipse(
     p: *mut InternalBuilder,
     center: LyonPoint,
@@ -96,6 +109,29 @@ pub fn add_rounded_rectangle(
     min: LyonPoint,
     max: LyonPoint,
     border_radius: f32,
+) {
+    let r = LyonVector {
+        x: border_radius,
+        y: border_radius,
+    };
+
+    let radii = LyonBorderRadii {
+        top_left: r,
+        top_right: r,
+        bottom_left: r,
+        bottom_right: r,
+    };
+
+    add_rounded_rectangle_with_radii(p, min, max, radii);
+}
+
+// Corners follow CSS `border-radius`: a corner with a zero radius on either axis is square,
+// and if adjacent corners overlap then all radii are scaled down by the same factor.
+pub fn add_rounded_rectangle_with_radii(
+    p: *mut InternalBuilder,
+    min: LyonPoint,
+    max: LyonPoint,
+    radii: LyonBorderRadii,
 ) {
     let w = max.x - min.x;
     let h = max.y - min.y;
@@ -106,32 +142,38 @@ pub fn add_rounded_rectangle(
     let x_max = max.x;
     let y_max = max.y;
 
-    let min_wh = w.min(h);
-    let mut tl = border_radius.abs().min(min_wh);
-    let mut tr = border_radius.abs().min(min_wh);
-    let mut bl = border_radius.abs().min(min_wh);
-    let mut br = border_radius.abs().min(min_wh);
+    let corner = |r: LyonVector| {
+        if r.x == 0.0 || r.y == 0.0 {
+            vector(0.0, 0.0)
+        } else {
+            vector(r.x.abs(), r.y.abs())
+        }
+    };
 
-    // clamp border radii if they don't fit in the rectangle.
-    if tl + tr > w {
-        let x = (tl + tr - w) * 0.5;
-        tl -= x;
-        tr -= x;
-    }
-    if bl + br > w {
-        let x = (bl + br - w) * 0.5;
-        bl -= x;
-        br -= x;
-    }
-    if tr + br > h {
-        let x = (tr + br - h) * 0.5;
-        tr -= x;
-        br -= x;
-    }
-    if tl + bl > h {
-        let x = (tl + bl - h) * 0.5;
-        tl -= x;
-        bl -= x;
+    let mut tl = corner(radii.top_left);
+    let mut tr = corner(radii.top_right);
+    let mut bl = corner(radii.bottom_left);
+    let mut br = corner(radii.bottom_right);
+
+    // scale the border radii down if they don't fit in the rectangle.
+    let fit = |length: f32, a: f32, b: f32| {
+        if a + b > length {
+            length / (a + b)
+        } else {
+            1.0
+        }
+    };
+
+    let scale = fit(w, tl.x, tr.x)
+        .min(fit(w, bl.x, br.x))
+        .min(fit(h, tl.y, bl.y))
+        .min(fit(h, tr.y, br.y));
+
+    if scale < 1.0 {
+        tl *= scale;
+        tr *= scale;
+        bl *= scale;
+        br *= scale;
     }
 
     // https://spencermortensen.com/articles/bezier-circle/
@@ -150,43 +192,414 @@ pub fn add_rounded_rectangle(
     let bl_corner = point(x_min, y_max);
 
     let points = [
-        point(x_min, y_min + tl),           // begin
-        tl_corner + vector(0.0, tl - tl_d), // control
-        tl_corner + vector(tl - tl_d, 0.0), // control
-        tl_corner + vector(tl, 0.0),        // end
-        point(x_max - tr, y_min),
-        tr_corner + vector(-tr + tr_d, 0.0),
-        tr_corner + vector(0.0, tr - tr_d),
-        tr_corner + vector(0.0, tr),
-        point(x_max, y_max - br),
-        br_corner + vector(0.0, -br + br_d),
-        br_corner + vector(-br + br_d, 0.0),
-        br_corner + vector(-br, 0.0),
-        point(x_min + bl, y_max),
-        bl_corner + vector(bl - bl_d, 0.0),
-        bl_corner + vector(0.0, -bl + bl_d),
-        bl_corner + vector(0.0, -bl),
+        point(x_min, y_min + tl.y),                 // begin
+        tl_corner + vector(0.0, tl.y - tl_d.y),     // control
+        tl_corner + vector(tl.x - tl_d.x, 0.0),     // control
+        tl_corner + vector(tl.x, 0.0),              // end
+        point(x_max - tr.x, y_min),
+        tr_corner + vector(-tr.x + tr_d.x, 0.0),
+        tr_corner + vector(0.0, tr.y - tr_d.y),
+        tr_corner + vector(0.0, tr.y),
+        point(x_max, y_max - br.y),
+        br_corner + vector(0.0, -br.y + br_d.y),
+        br_corner + vector(-br.x + br_d.x, 0.0),
+        br_corner + vector(-br.x, 0.0),
+        point(x_min + bl.x, y_max),
+        bl_corner + vector(bl.x - bl_d.x, 0.0),
+        bl_corner + vector(0.0, -bl.y + bl_d.y),
+        bl_corner + vector(0.0, -bl.y),
     ];
 
     assert!(!p.is_null());
     let builder = unsafe { &mut (*p) };
 
     builder.move_to(points[0]);
-    if tl > 0.0 {
+    if tl.x > 0.0 {
         builder.cubic_bezier_to(points[1], points[2], points[3]);
     }
     builder.line_to(points[4]);
-    if tl > 0.0 {
+    if tr.x > 0.0 {
         builder.cubic_bezier_to(points[5], points[6], points[7]);
     }
     builder.line_to(points[8]);
-    if br > 0.0 {
+    if br.x > 0.0 {
         builder.cubic_bezier_to(points[9], points[10], points[11]);
     }
     builder.line_to(points[12]);
-    if bl > 0.0 {
+    if bl.x > 0.0 {
         builder.cubic_bezier_to(points[13], points[14], points[15]);
     }
 
     builder.close()
 }
+
+// Points evenly spaced on a circle, starting at `rotation` radians from the x axis.
+fn points_on_circle(center: Point, radius: f32, count: u32, rotation: f32) -> Vec<Point> {
+    (0..count)
+        .map(|i| {
+            let angle = rotation + 2.0 * PI * (i as f32) / (count as f32);
+            center + vector(angle.cos(), angle.sin()) * radius
+        })
+        .collect()
+}
+
+// Adds the arc as curves to the current sub-path, which must end at the start of the arc.
+fn arc_to(builder: &mut InternalBuilder, center: Point, radius: f32, start_angle: f32, sweep_angle: f32) {
+    let arc = Arc {
+        center,
+        radii: vector(radius, radius),
+        x_rotation: Angle::radians(0.0),
+        start_angle: Angle::radians(start_angle),
+        sweep_angle: Angle::radians(sweep_angle),
+    };
+
+    arc.for_each_quadratic_bezier(&mut |curve: &QuadraticBezierSegment<f32>| {
+        builder.quadratic_bezier_to(curve.ctrl, curve.to);
+    });
+}
+
+pub fn add_regular_polygon(
+    p: *mut InternalBuilder,
+    center: LyonPoint,
+    radius: f32,
+    sides: u32,
+    rotation: f32,
+) {
+    assert!(!p.is_null());
+
+    // Fewer sides do not enclose anything.
+    if sides < 3 {
+        return;
+    }
+
+    let points = points_on_circle(center.into(), radius.abs(), sides, rotation);
+
+    let builder = unsafe { &mut (*p) };
+    builder.add_polygon(Polygon {
+        points: &points,
+        closed: true,
+    });
+}
+
+pub fn add_star(
+    p: *mut InternalBuilder,
+    center: LyonPoint,
+    outer_radius: f32,
+    inner_radius: f32,
+    branches: u32,
+    rotation: f32,
+) {
+    assert!(!p.is_null());
+
+    if branches < 2 {
+        return;
+    }
+
+    let outer = points_on_circle(center.into(), outer_radius.abs(), branches, rotation);
+    let inner = points_on_circle(
+        center.into(),
+        inner_radius.abs(),
+        branches,
+        rotation + PI / (branches as f32),
+    );
+
+    let points: Vec<Point> = outer
+        .iter()
+        .zip(inner.iter())
+        .flat_map(|(o, i)| [*o, *i])
+        .collect();
+
+    let builder = unsafe { &mut (*p) };
+    builder.add_polygon(Polygon {
+        points: &points,
+        closed: true,
+    });
+}
+
+pub fn add_pie(
+    p: *mut InternalBuilder,
+    c: LyonPoint,
+    radius: f32,
+    start_angle: f32,
+    sweep_angle: f32,
+) {
+    assert!(!p.is_null());
+
+    let radius = radius.abs();
+    let center: Point = c.into();
+    let sweep_angle = sweep_angle.clamp(-2.0 * PI, 2.0 * PI);
+
+    let builder = unsafe { &mut (*p) };
+
+    builder.move_to(center);
+    builder.line_to(center + vector(start_angle.cos(), start_angle.sin()) * radius);
+    arc_to(builder, center, radius, start_angle, sweep_angle);
+    builder.close();
+}
+
+pub fn add_annular_sector(
+    p: *mut InternalBuilder,
+    c: LyonPoint,
+    inner_radius: f32,
+    outer_radius: f32,
+    start_angle: f32,
+    sweep_angle: f32,
+) {
+    assert!(!p.is_null());
+
+    let inner_radius = inner_radius.abs();
+    let outer_radius = outer_radius.abs();
+    if inner_radius == 0.0 {
+        return add_pie(p, c, outer_radius, start_angle, sweep_angle);
+    }
+
+    let center: Point = c.into();
+    let end_angle = start_angle + sweep_angle;
+    let direction = |angle: f32| vector(angle.cos(), angle.sin());
+
+    let builder = unsafe { &mut (*p) };
+
+    if sweep_angle.abs() >= 2.0 * PI {
+        // A full ring, the inner circle goes the other way around to cut the hole.
+        let sweep_angle = 2.0 * PI * sweep_angle.signum();
+
+        builder.move_to(center + direction(start_angle) * outer_radius);
+        arc_to(builder, center, outer_radius, start_angle, sweep_angle);
+        builder.close();
+
+        builder.move_to(center + direction(start_angle) * inner_radius);
+        arc_to(builder, center, inner_radius, start_angle, -sweep_angle);
+        builder.close();
+        return;
+    }
+
+    builder.move_to(center + direction(start_angle) * outer_radius);
+    arc_to(builder, center, outer_radius, start_angle, sweep_angle);
+    builder.line_to(center + direction(end_angle) * inner_radius);
+    arc_to(builder, center, inner_radius, end_angle, -sweep_angle);
+    builder.close();
+}
+
+pub fn add_capsule(p: *mut InternalBuilder, from: LyonPoint, to: LyonPoint, radius: f32) {
+    assert!(!p.is_null());
+
+    if from.x == to.x && from.y == to.y {
+        return add_circle(p, from, radius);
+    }
+
+    let radius = radius.abs();
+    let from: Point = from.into();
+    let to: Point = to.into();
+    let axis = to - from;
+
+    // Left hand side of the axis, and the angle at which the end caps start.
+    let normal: Vector = vector(-axis.y, axis.x).normalize();
+    let normal_angle = normal.angle_from_x_axis().radians;
+
+    let builder = unsafe { &mut (*p) };
+
+    builder.move_to(from + normal * radius);
+    builder.line_to(to + normal * radius);
+    arc_to(builder, to, radius, normal_angle, -PI);
+    builder.line_to(from - normal * radius);
+    arc_to(builder, from, radius, normal_angle + PI, -PI);
+    builder.close();
+}
+
+pub fn add_arrow(
+    p: *mut InternalBuilder,
+    from: LyonPoint,
+    to: LyonPoint,
+    shaft_width: f32,
+    head_width: f32,
+    head_length: f32,
+) {
+    assert!(!p.is_null());
+
+    let from: Point = from.into();
+    let to: Point = to.into();
+
+    let axis = to - from;
+    let length = axis.length();
+    if length == 0.0 {
+        return;
+    }
+
+    let direction = axis / length;
+    let normal = vector(-direction.y, direction.x);
+
+    let shaft = normal * (shaft_width.abs() * 0.5);
+    let head = normal * (head_width.abs() * 0.5);
+    let head_base = to - direction * head_length.abs().min(length);
+
+    let points = [
+        from + shaft,
+        head_base + shaft,
+        head_base + head,
+        to,
+        head_base - head,
+        head_base - shaft,
+        from - shaft,
+    ];
+
+    let builder = unsafe { &mut (*p) };
+    builder.add_polygon(Polygon {
+        points: &points,
+        closed: true,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lyon::path::builder::WithSvg;
+    use lyon::path::path::BuilderImpl;
+
+    use crate::path::polylines;
+
+    fn v(x: f32, y: f32) -> LyonVector {
+        LyonVector { x, y }
+    }
+
+    fn flattened_rounded_rect(min: LyonPoint, max: LyonPoint, radii: LyonBorderRadii) -> Vec<Point> {
+        let mut builder = WithSvg::new(BuilderImpl::new());
+        add_rounded_rectangle_with_radii(&mut builder, min, max, radii);
+        let path = builder.build();
+
+        let mut lines = polylines(&path, 0.001);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].closed);
+
+        lines.remove(0).points
+    }
+
+    // Checks that every flattened point is either on a side of the rectangle, outside of the
+    // corners, or on the ellipse of its corner. `expected` are the radii of the top left, top
+    // right, bottom right and bottom left corners, where "top" is `min.y`.
+    fn check_outline(min: LyonPoint, max: LyonPoint, radii: LyonBorderRadii, expected: [(f32, f32); 4]) {
+        let points = flattened_rounded_rect(min, max, radii);
+
+        let corners = [
+            (point(min.x, min.y), vector(1.0, 1.0)),
+            (point(max.x, min.y), vector(-1.0, 1.0)),
+            (point(max.x, max.y), vector(-1.0, -1.0)),
+            (point(min.x, max.y), vector(1.0, -1.0)),
+        ];
+
+        let mut on_ellipse = [0; 4];
+        for p in &points {
+            let corner = corners.iter().zip(expected.iter()).enumerate().find(|(_, ((c, inward), (rx, ry)))| {
+                (p.x - c.x) * inward.x < *rx && (p.y - c.y) * inward.y < *ry
+            });
+
+            match corner {
+                Some((i, ((c, inward), (rx, ry)))) => {
+                    let center = point(c.x + inward.x * rx, c.y + inward.y * ry);
+                    let dx = (p.x - center.x) / rx;
+                    let dy = (p.y - center.y) / ry;
+                    let error = (dx * dx + dy * dy).sqrt() - 1.0;
+                    // The cubic approximation of a quarter ellipse is within 0.03% of its radii.
+                    assert!(error.abs() < 1e-3, "{:?} is {} off the ellipse of corner {}", p, error, i);
+                    on_ellipse[i] += 1;
+                }
+                None => {
+                    let side = (p.x - min.x)
+                        .abs()
+                        .min((p.x - max.x).abs())
+                        .min((p.y - min.y).abs())
+                        .min((p.y - max.y).abs());
+                    assert!(side < 1e-4, "{:?} is not on the rectangle", p);
+                }
+            }
+        }
+
+        for (i, &(rx, ry)) in expected.iter().enumerate() {
+            let (c, _) = corners[i];
+            let has_corner_point = points.iter().any(|p| (*p - c).length() < 1e-4);
+            if rx > 0.0 && ry > 0.0 {
+                assert!(on_ellipse[i] > 2, "corner {} is not rounded", i);
+                assert!(!has_corner_point, "corner {} is square", i);
+            } else {
+                assert!(has_corner_point, "corner {} is not square", i);
+            }
+        }
+    }
+
+    #[test]
+    fn elliptical_corners() {
+        let radii = LyonBorderRadii {
+            top_left: v(10.0, 5.0),
+            top_right: v(20.0, 15.0),
+            bottom_left: v(5.0, 25.0),
+            bottom_right: v(30.0, 10.0),
+        };
+
+        check_outline(
+            LyonPoint { x: 10.0, y: 20.0 },
+            LyonPoint { x: 110.0, y: 80.0 },
+            radii,
+            [(10.0, 5.0), (20.0, 15.0), (30.0, 10.0), (5.0, 25.0)],
+        );
+    }
+
+    // The top right corner used to be drawn only when the top left one was rounded.
+    #[test]
+    fn top_right_corner_without_top_left() {
+        let radii = LyonBorderRadii {
+            top_left: v(0.0, 0.0),
+            top_right: v(20.0, 10.0),
+            bottom_left: v(0.0, 0.0),
+            bottom_right: v(0.0, 0.0),
+        };
+
+        check_outline(
+            LyonPoint { x: 0.0, y: 0.0 },
+            LyonPoint { x: 100.0, y: 50.0 },
+            radii,
+            [(0.0, 0.0), (20.0, 10.0), (0.0, 0.0), (0.0, 0.0)],
+        );
+    }
+
+    // A zero radius on either axis gives a square corner.
+    #[test]
+    fn zero_radius_on_one_axis() {
+        let radii = LyonBorderRadii {
+            top_left: v(10.0, 0.0),
+            top_right: v(0.0, 10.0),
+            bottom_left: v(10.0, 10.0),
+            bottom_right: v(10.0, 10.0),
+        };
+
+        check_outline(
+            LyonPoint { x: 0.0, y: 0.0 },
+            LyonPoint { x: 100.0, y: 50.0 },
+            radii,
+            [(0.0, 0.0), (0.0, 0.0), (10.0, 10.0), (10.0, 10.0)],
+        );
+    }
+
+    // Like CSS, radii that do not fit are all scaled down by the same factor: here the top
+    // corners need 160 out of a width of 100, and the right ones 60 out of a height of 50.
+    #[test]
+    fn radii_are_scaled_to_fit() {
+        let radii = LyonBorderRadii {
+            top_left: v(80.0, 20.0),
+            top_right: v(80.0, 40.0),
+            bottom_left: v(10.0, 10.0),
+            bottom_right: v(10.0, 20.0),
+        };
+
+        let scale = 100.0 / 160.0;
+        check_outline(
+            LyonPoint { x: 0.0, y: 0.0 },
+            LyonPoint { x: 100.0, y: 50.0 },
+            radii,
+            [
+                (80.0 * scale, 20.0 * scale),
+                (80.0 * scale, 40.0 * scale),
+                (10.0 * scale, 20.0 * scale),
+                (10.0 * scale, 10.0 * scale),
+            ],
+        );
+    }
+}
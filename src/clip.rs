@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+
+use lyon::math::{point, Box2D, Point};
+use lyon::path::Path;
+use lyon::tessellation::{GeometryBuilderError, VertexBuffers};
+
+use crate::geometry::GeometryIndex;
+use crate::path_properties::fill_triangles;
+use crate::vertex::Vertex;
+
+// The region that tessellated geometry is clipped to: the intersection of a rectangle
+// and the area filled by a path, when they are given.
+pub struct Clip {
+    rect: Option<[Point; 4]>,
+    // The clip path, as the disjoint convex pieces that cover its filled area.
+    triangles: Option<Vec<([Point; 3], Box2D)>>,
+}
+
+impl Clip {
+    // None if there is nothing to clip against.
+    pub fn new(rect: Option<Box2D>, path: Option<&Path>, tolerance: f32) -> Option<Clip> {
+        if rect.is_none() && path.is_none() {
+            return None;
+        }
+
+        let rect = rect.map(|r| {
+            let min = r.min.min(r.max);
+            let max = r.min.max(r.max);
+            [min, point(max.x, min.y), max, point(min.x, max.y)]
+        });
+
+        // Clip paths use the non-zero fill rule, like SVG's default `clip-rule`.
+        let triangles = path.map(|path| {
+            fill_triangles(path, 1, tolerance)
+                .into_iter()
+                .filter(|t| (t[1] - t[0]).cross(t[2] - t[0]) != 0.0)
+                .map(|t| (t, Box2D::from_points(t.iter())))
+                .collect()
+        });
+
+        Some(Clip { rect, triangles })
+    }
+}
+
+// A clip line, by its sorted end points, so that the edges that neighbouring clip triangles
+// share are the same line.
+type Line = [(u32, u32); 2];
+
+fn line(from: Point, to: Point) -> Line {
+    let mut line = [(from.x.to_bits(), from.y.to_bits()), (to.x.to_bits(), to.y.to_bits())];
+    line.sort();
+    line
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+enum Edge {
+    // An edge of the clipped triangle, by its sorted vertex indices.
+    Triangle(usize, usize),
+    Clip(Line),
+}
+
+// A point of a clipped triangle, with its barycentric weights in the original triangle.
+#[derive(Copy, Clone)]
+struct ClipPoint {
+    position: Point,
+    weights: [f32; 3],
+    // The edge that the polygon follows from this point to the next one.
+    edge: Edge,
+    // The edge and the clip line this point was cut at, None for the triangle's vertices.
+    cut: Option<(Edge, Line)>,
+}
+
+// Sutherland-Hodgman against each edge of a convex polygon, of either orientation.
+fn clip_polygon(polygon: &[ClipPoint], convex: &[Point]) -> Vec<ClipPoint> {
+    let mut area = 0.0;
+    for i in 0..convex.len() {
+        area += convex[i].to_vector().cross(convex[(i + 1) % convex.len()].to_vector());
+    }
+    let orientation = if area < 0.0 { -1.0 } else { 1.0 };
+
+    let mut output = polygon.to_vec();
+    for i in 0..convex.len() {
+        if output.is_empty() {
+            break;
+        }
+
+        let from = convex[i];
+        let to = convex[(i + 1) % convex.len()];
+        let clip_line = line(from, to);
+        let edge = to - from;
+        let side = |p: Point| edge.cross(p - from) * orientation;
+
+        let input = std::mem::take(&mut output);
+        for j in 0..input.len() {
+            let current = input[j];
+            let next = input[(j + 1) % input.len()];
+            let (d_current, d_next) = (side(current.position), side(next.position));
+
+            if d_current >= 0.0 {
+                output.push(current);
+            }
+
+            if (d_current >= 0.0) != (d_next >= 0.0) {
+                let t = d_current / (d_current - d_next);
+                let mut weights = [0.0; 3];
+                for (k, weight) in weights.iter_mut().enumerate() {
+                    *weight = current.weights[k] + (next.weights[k] - current.weights[k]) * t;
+                }
+
+                // The same two lines give the same point whichever was cut first.
+                let cut = match current.edge {
+                    Edge::Clip(other) if other > clip_line => (Edge::Clip(clip_line), other),
+                    edge => (edge, clip_line),
+                };
+
+                // Leaving the clip region, the polygon continues along the clip line.
+                output.push(ClipPoint {
+                    position: current.position.lerp(next.position, t),
+                    weights,
+                    edge: if d_current >= 0.0 { Edge::Clip(clip_line) } else { current.edge },
+                    cut: Some(cut),
+                });
+            }
+        }
+    }
+
+    output
+}
+
+fn interpolate(vertices: &[Vertex; 3], weights: [f32; 3]) -> Vertex {
+    let mix = |f: &dyn Fn(&Vertex) -> f32| {
+        f(&vertices[0]) * weights[0] + f(&vertices[1]) * weights[1] + f(&vertices[2]) * weights[2]
+    };
+
+    Vertex {
+        position: [mix(&|v| v.position[0]), mix(&|v| v.position[1])],
+        original_position: [mix(&|v| v.original_position[0]), mix(&|v| v.original_position[1])],
+        normal: [mix(&|v| v.normal[0]), mix(&|v| v.normal[1])],
+        centerline_distance: mix(&|v| v.centerline_distance),
+        path_distance: mix(&|v| v.path_distance),
+        ..vertices[0]
+    }
+}
+
+// Clips the triangles appended to the geometry since `first_vertex` and `first_index`. Unclipped
+// vertices are kept, new ones are interpolated from the triangle they were cut from and shared
+// with the neighbouring triangles cut at the same edge, and the vertices that are no longer used
+// are removed.
+pub fn clip_geometry<IndexType: GeometryIndex>(
+    geometry: &mut VertexBuffers<Vertex, IndexType>,
+    first_vertex: usize,
+    first_index: usize,
+    clip: &Clip,
+) -> Result<(), GeometryBuilderError> {
+    let triangles: Vec<[usize; 3]> = geometry.indices[first_index..]
+        .chunks_exact(3)
+        .map(|t| [t[0].to_usize(), t[1].to_usize(), t[2].to_usize()])
+        .collect();
+
+    let mut indices: Vec<usize> = Vec::with_capacity(triangles.len() * 3);
+    let mut pieces: Vec<Vec<ClipPoint>> = Vec::new();
+
+    // Points cut from an edge of the triangles, shared by the triangles on both sides of it.
+    let mut on_edges: HashMap<(Edge, Line), usize> = HashMap::new();
+    // Points where two clip lines cross inside a triangle, shared by its pieces.
+    let mut inside: HashMap<(Edge, Line), usize> = HashMap::new();
+
+    for triangle in triangles {
+        let corners = [
+            geometry.vertices[triangle[0]],
+            geometry.vertices[triangle[1]],
+            geometry.vertices[triangle[2]],
+        ];
+        let position = |i: usize| point(corners[i].position[0], corners[i].position[1]);
+        let edge = |i: usize| {
+            let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+            Edge::Triangle(a.min(b), a.max(b))
+        };
+
+        // Hairline strokes are made of triangles without area, which are kept.
+        let degenerate = (position(1) - position(0)).cross(position(2) - position(0)) == 0.0;
+
+        let mut polygon = vec![
+            ClipPoint { position: position(0), weights: [1.0, 0.0, 0.0], edge: edge(0), cut: None },
+            ClipPoint { position: position(1), weights: [0.0, 1.0, 0.0], edge: edge(1), cut: None },
+            ClipPoint { position: position(2), weights: [0.0, 0.0, 1.0], edge: edge(2), cut: None },
+        ];
+        inside.clear();
+
+        if let Some(rect) = &clip.rect {
+            polygon = clip_polygon(&polygon, rect);
+        }
+
+        pieces.clear();
+        match &clip.triangles {
+            Some(clip_triangles) => {
+                let bounds = Box2D::from_points(polygon.iter().map(|p| p.position));
+                for (clip_triangle, clip_bounds) in clip_triangles {
+                    if polygon.len() < 3 || !bounds.intersects(clip_bounds) {
+                        continue;
+                    }
+
+                    pieces.push(clip_polygon(&polygon, clip_triangle));
+                }
+            }
+            None => pieces.push(polygon),
+        }
+
+        for piece in pieces.iter().filter(|piece| piece.len() >= 3) {
+            let mut piece_indices = Vec::with_capacity(piece.len());
+            for p in piece {
+                // Points that were not cut keep their vertex.
+                let original = p.weights.iter().position(|&w| w == 1.0);
+                match (original, p.cut) {
+                    (Some(i), _) => piece_indices.push(triangle[i]),
+                    (None, None) => unreachable!("the triangle's vertices have a weight of 1"),
+                    (None, Some(cut)) => {
+                        let shared = match cut.0 {
+                            Edge::Triangle(..) => &mut on_edges,
+                            Edge::Clip(_) => &mut inside,
+                        };
+                        let index = *shared.entry(cut).or_insert_with(|| {
+                            geometry.vertices.push(interpolate(&corners, p.weights));
+                            geometry.vertices.len() - 1
+                        });
+                        piece_indices.push(index);
+                    }
+                }
+            }
+
+            // Only the triangles that clipping made degenerate are dropped.
+            for i in 1..piece.len() - 1 {
+                let area = (piece[i].position - piece[0].position)
+                    .cross(piece[i + 1].position - piece[0].position);
+                if area != 0.0 || degenerate {
+                    indices.extend_from_slice(&[piece_indices[0], piece_indices[i], piece_indices[i + 1]]);
+                }
+            }
+        }
+    }
+
+    // Removes the vertices that are no longer referenced, keeping the order of the others.
+    let mut used = vec![false; geometry.vertices.len() - first_vertex];
+    for &index in &indices {
+        if index >= first_vertex {
+            used[index - first_vertex] = true;
+        }
+    }
+
+    let mut remap = vec![0; used.len()];
+    let mut next = first_vertex;
+    for (offset, used) in used.into_iter().enumerate() {
+        if used {
+            remap[offset] = next;
+            geometry.vertices[next] = geometry.vertices[first_vertex + offset];
+            next += 1;
+        }
+    }
+    geometry.vertices.truncate(next);
+
+    geometry.indices.truncate(first_index);
+    for index in indices {
+        let index = if index >= first_vertex {
+            remap[index - first_vertex]
+        } else {
+            index
+        };
+
+        geometry
+            .indices
+            .push(IndexType::from_usize(index).ok_or(GeometryBuilderError::TooManyVertices)?);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geometry(positions: &[(f32, f32)], indices: &[u16]) -> VertexBuffers<Vertex, u16> {
+        let vertices = positions
+            .iter()
+            .map(|&(x, y)| Vertex {
+                position: [x, y],
+                original_position: [x, y],
+                normal: [0.0, 0.0],
+                color: 0,
+                primitive_type: 0,
+                fill_ind: 0,
+                shape_ind: 0,
+                centerline_distance: 0.0,
+                path_distance: 0.0,
+            })
+            .collect();
+
+        VertexBuffers { vertices, indices: indices.to_vec() }
+    }
+
+    fn rect(min: (f32, f32), max: (f32, f32)) -> Clip {
+        Clip::new(Some(Box2D::new(point(min.0, min.1), point(max.0, max.1))), None, 0.1).unwrap()
+    }
+
+    #[test]
+    fn hairlines_are_clipped() {
+        // A hairline stroke from (0, 0) to (10, 0), as two triangles without area.
+        let mut hairline = geometry(&[(0.0, 0.0), (0.0, 0.0), (10.0, 0.0), (10.0, 0.0)], &[0, 1, 2, 1, 3, 2]);
+
+        clip_geometry(&mut hairline, 0, 0, &rect((-1.0, -1.0), (20.0, 1.0))).unwrap();
+        assert_eq!(hairline.indices, [0, 1, 2, 1, 3, 2]);
+        assert_eq!(hairline.vertices.len(), 4);
+
+        clip_geometry(&mut hairline, 0, 0, &rect((-1.0, -1.0), (5.0, 1.0))).unwrap();
+        assert!(!hairline.indices.is_empty());
+        assert!(hairline.vertices.iter().all(|v| v.position[0] <= 5.0));
+        // The two vertices on the path and the three cuts, one of them on the shared edge.
+        assert_eq!(hairline.vertices.len(), 5);
+    }
+
+    #[test]
+    fn cuts_on_shared_edges_share_vertices() {
+        let mut square = geometry(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)], &[0, 1, 2, 0, 2, 3]);
+        clip_geometry(&mut square, 0, 0, &rect((-1.0, -1.0), (5.0, 11.0))).unwrap();
+
+        // (0, 0) and (0, 10) are kept, and the diagonal is cut once at (5, 5).
+        assert_eq!(square.vertices.len(), 5);
+        for (i, a) in square.vertices.iter().enumerate() {
+            for b in &square.vertices[i + 1..] {
+                assert_ne!(a.position, b.position);
+            }
+        }
+        assert_eq!(square.indices.len(), 9);
+        assert!(square.indices.iter().all(|&i| (i as usize) < square.vertices.len()));
+    }
+}
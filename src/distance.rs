@@ -0,0 +1,163 @@
+use lyon::algorithms::hit_test::path_winding_number_at_position;
+use lyon::geom::CubicBezierSegment;
+use lyon::math::{vector, Point};
+use lyon::path::{FillRule, Path};
+
+use crate::intersections::{segments, Segment};
+use crate::path::tolerance_or_default;
+use crate::simplify::second_derivative;
+use crate::types::{LyonPoint, LyonVector};
+
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct LyonClosestPoint {
+    pub position: LyonPoint,
+    pub distance: f32,
+    // Distance along the path from its start, the same measure as LyonPath_Length.
+    pub arc_length: f32,
+    // Unit tangent in the direction of the path.
+    pub tangent: LyonVector,
+    // Numbered like the segments of LyonIntersection.
+    pub segment: u32,
+    pub t: f32,
+}
+
+const CURVE_SAMPLES: usize = 16;
+const NEWTON_ITERATIONS: usize = 8;
+
+// Coarse sampling followed by Newton-Raphson steps on (B(t) - p) . B'(t) = 0.
+fn closest_t_on_curve(curve: &CubicBezierSegment<f32>, p: Point) -> f32 {
+    let mut best_t = 0.0;
+    let mut best_distance = f32::MAX;
+    for i in 0..=CURVE_SAMPLES {
+        let t = i as f32 / CURVE_SAMPLES as f32;
+        let distance = (curve.sample(t) - p).square_length();
+        if distance < best_distance {
+            best_distance = distance;
+            best_t = t;
+        }
+    }
+
+    let mut t = best_t;
+    for _ in 0..NEWTON_ITERATIONS {
+        let d = curve.sample(t) - p;
+        let d1 = curve.derivative(t);
+        let numerator = d.dot(d1);
+        let denominator = d1.dot(d1) + d.dot(second_derivative(curve, t));
+        if denominator == 0.0 {
+            break;
+        }
+
+        let next = (t - numerator / denominator).clamp(0.0, 1.0);
+        if (curve.sample(next) - p).square_length() > (curve.sample(t) - p).square_length() {
+            break;
+        }
+        t = next;
+    }
+
+    t
+}
+
+fn closest_t(segment: &Segment, p: Point) -> f32 {
+    match segment {
+        Segment::Line(line) => {
+            let v = line.to_vector();
+            let length = v.square_length();
+            if length == 0.0 {
+                0.0
+            } else {
+                ((p - line.from).dot(v) / length).clamp(0.0, 1.0)
+            }
+        }
+        Segment::Curve(curve) => closest_t_on_curve(curve, p),
+    }
+}
+
+pub fn closest_point(path: &Path, p: Point, tolerance: f32) -> Option<LyonClosestPoint> {
+    let segments = segments(path);
+
+    let mut best: Option<(usize, f32, f32)> = None;
+    for (i, segment) in segments.iter().enumerate() {
+        let t = closest_t(segment, p);
+        let distance = (segment.sample(t) - p).length();
+        if best.is_none_or(|(_, _, d)| distance < d) {
+            best = Some((i, t, distance));
+        }
+    }
+
+    let (index, t, distance) = best?;
+    let segment = &segments[index];
+
+    let arc_length = segments[..index]
+        .iter()
+        .map(|s| s.length(tolerance))
+        .sum::<f32>()
+        + segment.length_to(t, tolerance);
+
+    let position = segment.sample(t);
+    let derivative = segment.derivative(t);
+    let tangent = if derivative.square_length() > 0.0 {
+        derivative.normalize()
+    } else {
+        vector(0.0, 0.0)
+    };
+
+    Some(LyonClosestPoint {
+        position: LyonPoint {
+            x: position.x,
+            y: position.y,
+        },
+        distance,
+        arc_length,
+        tangent: LyonVector {
+            x: tangent.x,
+            y: tangent.y,
+        },
+        segment: index as u32,
+        t,
+    })
+}
+
+// Returns 0 and leaves `result` untouched if the path has no segment.
+#[no_mangle]
+pub extern fn LyonPath_ClosestPoint(p: *mut Path, point: LyonPoint, tolerance: f32, result: *mut LyonClosestPoint) -> i32 {
+    assert!(!p.is_null());
+    assert!(!result.is_null());
+    let path = unsafe { &*p };
+
+    match closest_point(path, point.into(), tolerance_or_default(tolerance)) {
+        Some(closest) => {
+            unsafe { *result = closest };
+            1
+        }
+        None => 0,
+    }
+}
+
+// Negative inside the filled area, positive outside.
+#[no_mangle]
+pub extern fn LyonPath_SignedDistance(p: *mut Path, point: LyonPoint, fill_rule: i32, tolerance: f32) -> f32 {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    let tolerance = tolerance_or_default(tolerance);
+    let point: Point = point.into();
+
+    let distance = match closest_point(path, point, tolerance) {
+        Some(closest) => closest.distance,
+        None => return f32::MAX,
+    };
+
+    let fill_rule = if fill_rule != 0 {
+        FillRule::NonZero
+    } else {
+        FillRule::EvenOdd
+    };
+
+    let winding = path_winding_number_at_position(&point, path.iter(), tolerance);
+    if fill_rule.is_in(winding as i16) {
+        -distance
+    } else {
+        distance
+    }
+}
@@ -1,43 +1,400 @@
+use lyon::math::{point, vector, Box2D, Point, Transform};
 use lyon::tessellation::*;
+use std::ops::Add;
 
+use crate::topology::{strip_triangles, TOPOLOGY_TRIANGLE_LIST, TOPOLOGY_TRIANGLE_STRIP};
+use crate::types::{LyonRect, LyonTransform};
 use crate::vertex::Vertex;
 
+// The index types of the geometry handed over to C.
+pub trait GeometryIndex: Copy + PartialEq + Add + From<VertexId> + geometry_builder::MaxIndex {
+    // Separates the strips of a triangle strip.
+    const RESTART: Self;
+
+    fn to_usize(self) -> usize;
+
+    // None if the index does not fit in the type.
+    fn from_usize(index: usize) -> Option<Self>;
+}
+
+impl GeometryIndex for u16 {
+    const RESTART: Self = u16::MAX;
+
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+
+    fn from_usize(index: usize) -> Option<Self> {
+        u16::try_from(index).ok()
+    }
+}
+
+impl GeometryIndex for u32 {
+    const RESTART: Self = u32::MAX;
+
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+
+    fn from_usize(index: usize) -> Option<Self> {
+        u32::try_from(index).ok()
+    }
+}
+
+// The geometry behind the C handles. The indices alone can't tell a strip from a list, so
+// the topology they were built with is kept next to them.
+#[derive(Clone)]
+pub struct Geometry<IndexType> {
+    pub buffers: VertexBuffers<Vertex, IndexType>,
+    // One of the `TOPOLOGY_*` constants.
+    pub topology: i32,
+}
+
+// The corners of each triangle, in the winding they are drawn with.
+pub fn triangles<IndexType: GeometryIndex>(geometry: &Geometry<IndexType>) -> Vec<[usize; 3]> {
+    match geometry.topology {
+        TOPOLOGY_TRIANGLE_LIST => (0..geometry.buffers.vertices.len() / 3)
+            .map(|t| [t * 3, t * 3 + 1, t * 3 + 2])
+            .collect(),
+        TOPOLOGY_TRIANGLE_STRIP => strip_triangles(&geometry.buffers.indices),
+        _ => geometry
+            .buffers
+            .indices
+            .chunks_exact(3)
+            .map(|t| [t[0].to_usize(), t[1].to_usize(), t[2].to_usize()])
+            .collect(),
+    }
+}
+
 #[no_mangle]
-pub extern fn LyonGeometry16_VerticesLength(p: *mut VertexBuffers<Vertex, u16>) -> u32 {
-    unsafe { (*p).vertices.len() as u32 }
+pub extern fn LyonGeometry16_VerticesLength(p: *mut Geometry<u16>) -> u32 {
+    unsafe { (*p).buffers.vertices.len() as u32 }
 }
 
 #[no_mangle]
-pub extern fn LyonGeometry16_IndicesLength(p: *mut VertexBuffers<Vertex, u16>) -> u32 {
-    unsafe { (*p).indices.len() as u32 }
+pub extern fn LyonGeometry16_IndicesLength(p: *mut Geometry<u16>) -> u32 {
+    unsafe { (*p).buffers.indices.len() as u32 }
 }
 
 #[no_mangle]
-pub extern fn LyonGeometry16_VerticesData(p: *mut VertexBuffers<Vertex, u16>) -> *const f32 {
-    unsafe { (*p).vertices.as_ptr() as *const f32 }
+pub extern fn LyonGeometry16_VerticesData(p: *mut Geometry<u16>) -> *const f32 {
+    unsafe { (*p).buffers.vertices.as_ptr() as *const f32 }
 }
 
 #[no_mangle]
-pub extern fn LyonGeometry16_IndicesData(p: *mut VertexBuffers<Vertex, u16>) -> *const u16 {
-    unsafe { (*p).indices.as_ptr() }
+pub extern fn LyonGeometry16_IndicesData(p: *mut Geometry<u16>) -> *const u16 {
+    unsafe { (*p).buffers.indices.as_ptr() }
 }
 
 #[no_mangle]
-pub extern fn LyonGeometry32_VerticesLength(p: *mut VertexBuffers<Vertex, u32>) -> u32 {
-    unsafe { (*p).vertices.len() as u32 }
+pub extern fn LyonGeometry16_Topology(p: *mut Geometry<u16>) -> i32 {
+    unsafe { (*p).topology }
 }
 
 #[no_mangle]
-pub extern fn LyonGeometry32_IndicesLength(p: *mut VertexBuffers<Vertex, u32>) -> u32 {
-    unsafe { (*p).indices.len() as u32 }
+pub extern fn LyonGeometry32_VerticesLength(p: *mut Geometry<u32>) -> u32 {
+    unsafe { (*p).buffers.vertices.len() as u32 }
 }
 
 #[no_mangle]
-pub extern fn LyonGeometry32_VerticesData(p: *mut VertexBuffers<Vertex, u32>) -> *const f32 {
-    unsafe { (*p).vertices.as_ptr() as *const f32 }
+pub extern fn LyonGeometry32_IndicesLength(p: *mut Geometry<u32>) -> u32 {
+    unsafe { (*p).buffers.indices.len() as u32 }
 }
 
 #[no_mangle]
-pub extern fn LyonGeometry32_IndicesData(p: *mut VertexBuffers<Vertex, u32>) -> *const u32 {
-    unsafe { (*p).indices.as_ptr() }
+pub extern fn LyonGeometry32_VerticesData(p: *mut Geometry<u32>) -> *const f32 {
+    unsafe { (*p).buffers.vertices.as_ptr() as *const f32 }
+}
+
+#[no_mangle]
+pub extern fn LyonGeometry32_IndicesData(p: *mut Geometry<u32>) -> *const u32 {
+    unsafe { (*p).buffers.indices.as_ptr() }
+}
+
+#[no_mangle]
+pub extern fn LyonGeometry32_Topology(p: *mut Geometry<u32>) -> i32 {
+    unsafe { (*p).topology }
+}
+
+// Moves the vertices, keeping `original_position` and the distances in path units. Normals
+// are transformed by the inverse transpose, so they stay perpendicular to the outline, and
+// keep their length.
+pub fn transform<IndexType>(geometry: &mut VertexBuffers<Vertex, IndexType>, transform: &Transform) {
+    let inverse = transform.inverse();
+
+    for v in geometry.vertices.iter_mut() {
+        let p = transform.transform_point(point(v.position[0], v.position[1]));
+        v.position = [p.x, p.y];
+
+        let n = vector(v.normal[0], v.normal[1]);
+        let length = n.length();
+        if length == 0.0 {
+            continue;
+        }
+
+        let transformed = match &inverse {
+            Some(inv) => vector(n.x * inv.m11 + n.y * inv.m12, n.x * inv.m21 + n.y * inv.m22),
+            None => transform.transform_vector(n),
+        };
+
+        if transformed.square_length() > 0.0 {
+            let transformed = transformed.normalize() * length;
+            v.normal = [transformed.x, transformed.y];
+        }
+    }
+}
+
+// Appends `src` to `dst`, rebasing its indices. Strips are kept apart by a restart index.
+// Fails without modifying `dst` if the indices do not
+// fit, or if the topologies differ.
+pub fn append<IndexType: GeometryIndex>(dst: &mut Geometry<IndexType>, src: &Geometry<IndexType>) -> bool {
+    if dst.buffers.vertices.is_empty() {
+        *dst = src.clone();
+        return true;
+    }
+
+    if src.buffers.vertices.is_empty() {
+        return true;
+    }
+
+    if dst.topology != src.topology {
+        return false;
+    }
+
+    let topology = dst.topology;
+    let (dst, src) = (&mut dst.buffers, &src.buffers);
+
+    let base = dst.vertices.len();
+    if !src.indices.is_empty() && base + src.vertices.len() > IndexType::RESTART.to_usize() {
+        return false;
+    }
+
+    // Strips would otherwise run on from one geometry into the other.
+    if topology == TOPOLOGY_TRIANGLE_STRIP && !dst.indices.is_empty() && !src.indices.is_empty() {
+        dst.indices.push(IndexType::RESTART);
+    }
+
+    dst.vertices.extend_from_slice(&src.vertices);
+    dst.indices.extend(src.indices.iter().map(|&i| {
+        if i == IndexType::RESTART {
+            i
+        } else {
+            IndexType::from_usize(i.to_usize() + base).unwrap()
+        }
+    }));
+
+    true
+}
+
+// None if an index does not fit in the new type.
+pub fn convert<Source: GeometryIndex, Target: GeometryIndex>(geometry: &Geometry<Source>) -> Option<Geometry<Target>> {
+    let buffers = &geometry.buffers;
+    if !buffers.indices.is_empty() && buffers.vertices.len() > Target::RESTART.to_usize() {
+        return None;
+    }
+
+    let indices = buffers
+        .indices
+        .iter()
+        .map(|&i| {
+            if i == Source::RESTART {
+                Some(Target::RESTART)
+            } else {
+                Target::from_usize(i.to_usize())
+            }
+        })
+        .collect::<Option<Vec<Target>>>()?;
+
+    Some(Geometry {
+        buffers: VertexBuffers {
+            vertices: buffers.vertices.clone(),
+            indices,
+        },
+        topology: geometry.topology,
+    })
+}
+
+#[no_mangle]
+pub extern fn LyonGeometry16_Transform(p: *mut Geometry<u16>, t: LyonTransform) {
+    assert!(!p.is_null());
+    transform(unsafe { &mut (*p).buffers }, &t.into())
+}
+
+#[no_mangle]
+pub extern fn LyonGeometry32_Transform(p: *mut Geometry<u32>, t: LyonTransform) {
+    assert!(!p.is_null());
+    transform(unsafe { &mut (*p).buffers }, &t.into())
+}
+
+#[no_mangle]
+pub extern fn LyonGeometry16_Append(dst: *mut Geometry<u16>, src: *mut Geometry<u16>) -> i32 {
+    assert!(!dst.is_null());
+    assert!(!src.is_null());
+    if dst == src {
+        // A geometry appended to itself is appended from a copy, as it can't be borrowed twice.
+        let copy = unsafe { (*src).clone() };
+        return append(unsafe { &mut *dst }, &copy) as i32;
+    }
+
+    append(unsafe { &mut *dst }, unsafe { &*src }) as i32
+}
+
+#[no_mangle]
+pub extern fn LyonGeometry32_Append(dst: *mut Geometry<u32>, src: *mut Geometry<u32>) -> i32 {
+    assert!(!dst.is_null());
+    assert!(!src.is_null());
+    if dst == src {
+        // A geometry appended to itself is appended from a copy, as it can't be borrowed twice.
+        let copy = unsafe { (*src).clone() };
+        return append(unsafe { &mut *dst }, &copy) as i32;
+    }
+
+    append(unsafe { &mut *dst }, unsafe { &*src }) as i32
+}
+
+// A new geometry, or null if it has too many vertices for 16-bit indices.
+#[no_mangle]
+pub extern fn LyonGeometry32_To16(p: *mut Geometry<u32>) -> *mut Geometry<u16> {
+    assert!(!p.is_null());
+    match convert(unsafe { &*p }) {
+        Some(geometry) => Box::into_raw(Box::new(geometry)),
+        None => std::ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub extern fn LyonGeometry16_To32(p: *mut Geometry<u16>) -> *mut Geometry<u32> {
+    assert!(!p.is_null());
+    Box::into_raw(Box::new(convert(unsafe { &*p }).unwrap()))
+}
+
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct LyonGeometryStats {
+    pub vertex_count: u32,
+    pub index_count: u32,
+    pub triangle_count: u32,
+    // Triangles with a repeated index or no area.
+    pub degenerate_triangle_count: u32,
+    pub bounds: LyonRect,
+    pub total_area: f32,
+    // The memory allocated for the vertices and indices.
+    pub memory_bytes: u64,
+}
+
+pub fn stats<IndexType: GeometryIndex>(geometry: &Geometry<IndexType>) -> LyonGeometryStats {
+    let triangles = triangles(geometry);
+
+    let geometry = &geometry.buffers;
+    let position = |i: usize| point(geometry.vertices[i].position[0], geometry.vertices[i].position[1]);
+
+    let mut degenerate_triangle_count = 0;
+    let mut total_area = 0.0;
+    for t in &triangles {
+        let area = (position(t[1]) - position(t[0])).cross(position(t[2]) - position(t[0])) * 0.5;
+        if t[0] == t[1] || t[1] == t[2] || t[2] == t[0] || area == 0.0 {
+            degenerate_triangle_count += 1;
+        }
+        total_area += area.abs();
+    }
+
+    let positions: Vec<Point> = (0..geometry.vertices.len()).map(position).collect();
+    let bounds = Box2D::from_points(positions);
+    let bounds = if geometry.vertices.is_empty() { Box2D::zero() } else { bounds };
+
+    let memory_bytes = geometry.vertices.capacity() * std::mem::size_of::<Vertex>()
+        + geometry.indices.capacity() * std::mem::size_of::<IndexType>();
+
+    LyonGeometryStats {
+        vertex_count: geometry.vertices.len() as u32,
+        index_count: geometry.indices.len() as u32,
+        triangle_count: triangles.len() as u32,
+        degenerate_triangle_count,
+        bounds: bounds.into(),
+        total_area,
+        memory_bytes: memory_bytes as u64,
+    }
+}
+
+#[no_mangle]
+pub extern fn LyonGeometry16_Stats(p: *mut Geometry<u16>) -> LyonGeometryStats {
+    assert!(!p.is_null());
+    stats(unsafe { &*p })
+}
+
+#[no_mangle]
+pub extern fn LyonGeometry32_Stats(p: *mut Geometry<u32>) -> LyonGeometryStats {
+    assert!(!p.is_null());
+    stats(unsafe { &*p })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::TOPOLOGY_INDEXED_TRIANGLE_LIST;
+
+    // A unit square at `x`, as two triangles.
+    fn square(x: f32, topology: i32) -> Geometry<u32> {
+        let vertices = vec![
+            Vertex::at(x, 0.0),
+            Vertex::at(x + 1.0, 0.0),
+            Vertex::at(x + 1.0, 1.0),
+            Vertex::at(x, 1.0),
+        ];
+        let indices = match topology {
+            TOPOLOGY_TRIANGLE_STRIP => vec![1, 2, 0, 3],
+            _ => vec![0, 1, 2, 0, 2, 3],
+        };
+
+        Geometry { buffers: VertexBuffers { vertices, indices }, topology }
+    }
+
+    #[test]
+    fn appended_strips_are_restarted() {
+        let mut geometry = square(0.0, TOPOLOGY_TRIANGLE_STRIP);
+        assert!(append(&mut geometry, &square(2.0, TOPOLOGY_TRIANGLE_STRIP)));
+
+        assert_eq!(geometry.buffers.indices, [1, 2, 0, 3, u32::RESTART, 5, 6, 4, 7]);
+        assert_eq!(triangles(&geometry), [[1, 2, 0], [0, 2, 3], [5, 6, 4], [4, 6, 7]]);
+    }
+
+    #[test]
+    fn appended_lists_are_not_restarted() {
+        let mut geometry = square(0.0, TOPOLOGY_INDEXED_TRIANGLE_LIST);
+        assert!(append(&mut geometry, &square(2.0, TOPOLOGY_INDEXED_TRIANGLE_LIST)));
+
+        assert_eq!(geometry.buffers.indices, [0, 1, 2, 0, 2, 3, 4, 5, 6, 4, 6, 7]);
+    }
+
+    #[test]
+    fn stats_of_strips() {
+        let list = stats(&square(0.0, TOPOLOGY_INDEXED_TRIANGLE_LIST));
+        let strip = stats(&square(0.0, TOPOLOGY_TRIANGLE_STRIP));
+
+        assert_eq!((list.triangle_count, list.total_area), (2, 1.0));
+        assert_eq!((strip.triangle_count, strip.total_area), (2, 1.0));
+        assert_eq!(strip.index_count, 4);
+        assert_eq!(strip.degenerate_triangle_count, 0);
+    }
+
+    #[test]
+    fn stats_of_degenerate_triangles() {
+        let mut geometry = square(0.0, TOPOLOGY_INDEXED_TRIANGLE_LIST);
+        geometry.buffers.vertices.push(Vertex::at(2.0, 0.0));
+        // A repeated index, then three corners on a line.
+        geometry.buffers.indices.extend_from_slice(&[0, 1, 1, 0, 1, 4]);
+
+        let stats = stats(&geometry);
+        assert_eq!(stats.triangle_count, 4);
+        assert_eq!(stats.degenerate_triangle_count, 2);
+        assert_eq!(stats.total_area, 1.0);
+        assert_eq!(stats.bounds.upper_right, [2.0, 1.0]);
+    }
+
+    #[test]
+    fn topologies_are_not_mixed() {
+        let mut geometry = square(0.0, TOPOLOGY_INDEXED_TRIANGLE_LIST);
+        assert!(!append(&mut geometry, &square(2.0, TOPOLOGY_TRIANGLE_STRIP)));
+        assert_eq!(geometry.buffers.vertices.len(), 4);
+    }
 }
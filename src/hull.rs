@@ -0,0 +1,243 @@
+use lyon::math::{point, vector, Point, Vector};
+use lyon::path::{Path, Polygon};
+
+use crate::path::{polylines, tolerance_or_default};
+use crate::types::{LyonPoint, LyonVector};
+
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct LyonOrientedRect {
+    pub center: LyonPoint,
+    // Unit vector along the first side, the second side is perpendicular to it.
+    pub axis: LyonVector,
+    pub half_extents: LyonVector,
+}
+
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct LyonCircle {
+    pub center: LyonPoint,
+    pub radius: f32,
+}
+
+// Andrew's monotone chain. The hull is counter-clockwise with the y axis pointing up,
+// without collinear points.
+pub fn convex_hull(points: &[Point]) -> Vec<Point> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| {
+        a.x.partial_cmp(&b.x)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.y.partial_cmp(&b.y).unwrap_or(std::cmp::Ordering::Equal))
+    });
+    sorted.dedup();
+
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    let turns_left = |hull: &[Point], p: Point| {
+        let a = hull[hull.len() - 2];
+        let b = hull[hull.len() - 1];
+        (b - a).cross(p - b) > 0.0
+    };
+
+    let mut lower: Vec<Point> = Vec::new();
+    for p in sorted.iter() {
+        while lower.len() >= 2 && !turns_left(&lower, *p) {
+            lower.pop();
+        }
+        lower.push(*p);
+    }
+
+    let mut upper: Vec<Point> = Vec::new();
+    for p in sorted.iter().rev() {
+        while upper.len() >= 2 && !turns_left(&upper, *p) {
+            upper.pop();
+        }
+        upper.push(*p);
+    }
+
+    lower.pop();
+    upper.pop();
+    lower.extend(upper);
+
+    lower
+}
+
+fn hull_of_path(path: &Path, tolerance: f32) -> Vec<Point> {
+    let points: Vec<Point> = polylines(path, tolerance)
+        .into_iter()
+        .flat_map(|polyline| polyline.points)
+        .collect();
+
+    convex_hull(&points)
+}
+
+// The minimum area rectangle has a side along one of the edges of the hull.
+pub fn minimum_area_rect(hull: &[Point]) -> (Point, Vector, Vector) {
+    if hull.is_empty() {
+        return (point(0.0, 0.0), vector(1.0, 0.0), vector(0.0, 0.0));
+    }
+
+    let mut best_area = f32::MAX;
+    let mut best = (hull[0], vector(1.0, 0.0), vector(0.0, 0.0));
+
+    for i in 0..hull.len() {
+        let edge = hull[(i + 1) % hull.len()] - hull[i];
+        let axis = if edge.square_length() > 0.0 {
+            edge.normalize()
+        } else {
+            vector(1.0, 0.0)
+        };
+        let normal = vector(-axis.y, axis.x);
+
+        let mut min = vector(f32::MAX, f32::MAX);
+        let mut max = vector(f32::MIN, f32::MIN);
+        for p in hull {
+            let projected = vector(p.to_vector().dot(axis), p.to_vector().dot(normal));
+            min = min.min(projected);
+            max = max.max(projected);
+        }
+
+        let size = max - min;
+        let area = size.x * size.y;
+        if area < best_area {
+            best_area = area;
+
+            let center = (min + max) * 0.5;
+            best = (
+                (axis * center.x + normal * center.y).to_point(),
+                axis,
+                size * 0.5,
+            );
+        }
+    }
+
+    best
+}
+
+fn circle_from_two(a: Point, b: Point) -> (Point, f32) {
+    let center = a.lerp(b, 0.5);
+    (center, (a - center).length())
+}
+
+fn circle_from_three(a: Point, b: Point, c: Point) -> (Point, f32) {
+    let ab = b - a;
+    let ac = c - a;
+    let d = 2.0 * ab.cross(ac);
+    if d == 0.0 {
+        // Collinear points, the circle goes through the two furthest apart.
+        let candidates = [circle_from_two(a, b), circle_from_two(a, c), circle_from_two(b, c)];
+        return candidates
+            .iter()
+            .copied()
+            .fold(candidates[0], |best, c| if c.1 > best.1 { c } else { best });
+    }
+
+    let ux = (ac.y * ab.square_length() - ab.y * ac.square_length()) / d;
+    let uy = (ab.x * ac.square_length() - ac.x * ab.square_length()) / d;
+    let offset = vector(ux, uy);
+
+    (a + offset, offset.length())
+}
+
+// Welzl's algorithm, in its iterative form.
+pub fn minimum_enclosing_circle(points: &[Point]) -> (Point, f32) {
+    if points.is_empty() {
+        return (point(0.0, 0.0), 0.0);
+    }
+
+    // Leave some room for rounding errors when testing whether a point is inside.
+    let contains = |circle: &(Point, f32), p: Point| (p - circle.0).length() <= circle.1 * (1.0 + 1.0e-5);
+
+    let mut circle = (points[0], 0.0);
+    for i in 1..points.len() {
+        if contains(&circle, points[i]) {
+            continue;
+        }
+
+        circle = (points[i], 0.0);
+        for j in 0..i {
+            if contains(&circle, points[j]) {
+                continue;
+            }
+
+            circle = circle_from_two(points[i], points[j]);
+            for k in 0..j {
+                if !contains(&circle, points[k]) {
+                    circle = circle_from_three(points[i], points[j], points[k]);
+                }
+            }
+        }
+    }
+
+    circle
+}
+
+// The hull of the flattened path, as a closed polygon.
+#[no_mangle]
+pub extern fn LyonPath_ConvexHull(p: *mut Path, tolerance: f32) -> *mut Path {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    let hull = hull_of_path(path, tolerance_or_default(tolerance));
+
+    let mut builder = Path::builder();
+    if !hull.is_empty() {
+        builder.add_polygon(Polygon {
+            points: &hull,
+            closed: true,
+        });
+    }
+
+    Box::into_raw(Box::new(builder.build()))
+}
+
+// Grown by the tolerance so that it contains the curves, not only the flattened path.
+#[no_mangle]
+pub extern fn LyonPath_MinimumAreaRect(p: *mut Path, tolerance: f32) -> LyonOrientedRect {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    let tolerance = tolerance_or_default(tolerance);
+    let hull = hull_of_path(path, tolerance);
+    let (center, axis, half_extents) = minimum_area_rect(&hull);
+
+    let grow = if hull.is_empty() { 0.0 } else { tolerance };
+
+    LyonOrientedRect {
+        center: LyonPoint {
+            x: center.x,
+            y: center.y,
+        },
+        axis: LyonVector {
+            x: axis.x,
+            y: axis.y,
+        },
+        half_extents: LyonVector {
+            x: half_extents.x + grow,
+            y: half_extents.y + grow,
+        },
+    }
+}
+
+// Grown by the tolerance so that it contains the curves, not only the flattened path.
+#[no_mangle]
+pub extern fn LyonPath_MinimumEnclosingCircle(p: *mut Path, tolerance: f32) -> LyonCircle {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    let tolerance = tolerance_or_default(tolerance);
+    let hull = hull_of_path(path, tolerance);
+    let (center, radius) = minimum_enclosing_circle(&hull);
+
+    let grow = if hull.is_empty() { 0.0 } else { tolerance };
+
+    LyonCircle {
+        center: LyonPoint {
+            x: center.x,
+            y: center.y,
+        },
+        radius: radius + grow,
+    }
+}
@@ -0,0 +1,410 @@
+use lyon::geom::{CubicBezierSegment, Line, LineSegment};
+use lyon::math::{Box2D, Point, Vector};
+use lyon::path::{Path, PathEvent};
+
+use crate::types::{LyonPoint, LyonVector};
+
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct LyonIntersection {
+    pub position: LyonPoint,
+    // Index and curve parameter of the segment on the first path.
+    pub segment_a: u32,
+    pub t_a: f32,
+    // Index and curve parameter on the second path, the ray or the line segment.
+    pub segment_b: u32,
+    pub t_b: f32,
+}
+
+// Quadratic curves are elevated to cubic ones, which keeps the curve parameter.
+#[derive(Copy, Clone, Debug)]
+pub enum Segment {
+    Line(LineSegment<f32>),
+    Curve(CubicBezierSegment<f32>),
+}
+
+impl Segment {
+    pub fn from(&self) -> Point {
+        match self {
+            Segment::Line(line) => line.from,
+            Segment::Curve(curve) => curve.from,
+        }
+    }
+
+    pub fn to(&self) -> Point {
+        match self {
+            Segment::Line(line) => line.to,
+            Segment::Curve(curve) => curve.to,
+        }
+    }
+
+    pub fn sample(&self, t: f32) -> Point {
+        match self {
+            Segment::Line(line) => line.sample(t),
+            Segment::Curve(curve) => curve.sample(t),
+        }
+    }
+
+    pub fn derivative(&self, t: f32) -> Vector {
+        match self {
+            Segment::Line(line) => line.to_vector(),
+            Segment::Curve(curve) => curve.derivative(t),
+        }
+    }
+
+    pub fn length(&self, tolerance: f32) -> f32 {
+        match self {
+            Segment::Line(line) => line.length(),
+            Segment::Curve(curve) => curve.approximate_length(tolerance),
+        }
+    }
+
+    // The length from the start of the segment to `t`.
+    pub fn length_to(&self, t: f32, tolerance: f32) -> f32 {
+        match self {
+            Segment::Line(line) => line.length() * t,
+            Segment::Curve(curve) => curve.before_split(t).approximate_length(tolerance),
+        }
+    }
+
+    fn bounding_box(&self) -> Box2D {
+        match self {
+            Segment::Line(line) => line.bounding_box(),
+            Segment::Curve(curve) => curve.fast_bounding_box(),
+        }
+    }
+}
+
+// The segments of a path, in the order of its events. Closing edges count as a segment
+// unless they are empty.
+pub fn segments(path: &Path) -> Vec<Segment> {
+    let mut segments = Vec::new();
+
+    for event in path.iter() {
+        match event {
+            PathEvent::Line { from, to } => {
+                segments.push(Segment::Line(LineSegment { from, to }));
+            }
+            PathEvent::Quadratic { from, ctrl, to } => {
+                segments.push(Segment::Curve(
+                    lyon::geom::QuadraticBezierSegment { from, ctrl, to }.to_cubic(),
+                ));
+            }
+            PathEvent::Cubic { from, ctrl1, ctrl2, to } => {
+                segments.push(Segment::Curve(CubicBezierSegment { from, ctrl1, ctrl2, to }));
+            }
+            PathEvent::End { last, first, close: true } if last != first => {
+                segments.push(Segment::Line(LineSegment { from: last, to: first }));
+            }
+            _ => {}
+        }
+    }
+
+    segments
+}
+
+// Unlike `LineSegment::intersection_t`, segments touching at their endpoints intersect.
+fn line_intersection_t(a: &LineSegment<f32>, b: &LineSegment<f32>) -> Option<(f32, f32)> {
+    let v1 = a.to_vector();
+    let v2 = b.to_vector();
+
+    let denom = v1.cross(v2);
+    if denom == 0.0 {
+        return None;
+    }
+
+    let v3 = b.from - a.from;
+    let t = v3.cross(v2) / denom;
+    let u = v3.cross(v1) / denom;
+
+    if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+        Some((t, u))
+    } else {
+        None
+    }
+}
+
+fn segment_intersections_t(a: &Segment, b: &Segment) -> Vec<(f32, f32)> {
+    if !a.bounding_box().inflate(1.0e-4, 1.0e-4).intersects(&b.bounding_box()) {
+        return Vec::new();
+    }
+
+    match (a, b) {
+        (Segment::Line(a), Segment::Line(b)) => line_intersection_t(a, b).into_iter().collect(),
+        (Segment::Curve(a), Segment::Line(b)) => a.line_segment_intersections_t(b).to_vec(),
+        (Segment::Line(a), Segment::Curve(b)) => b
+            .line_segment_intersections_t(a)
+            .iter()
+            .map(|&(t_b, t_a)| (t_a, t_b))
+            .collect(),
+        (Segment::Curve(a), Segment::Curve(b)) => a.cubic_intersections_t(b).to_vec(),
+    }
+}
+
+// For each segment, the one that starts where it ends: the next segment of its sub-path,
+// or the first one if the sub-path is closed.
+fn next_segments(segments: &[Segment]) -> Vec<Option<usize>> {
+    let mut next = vec![None; segments.len()];
+    let mut first = 0;
+
+    for i in 0..segments.len() {
+        let end = segments[i].to();
+        if segments.get(i + 1).is_some_and(|s| s.from() == end) {
+            next[i] = Some(i + 1);
+        } else {
+            if segments[first].from() == end {
+                next[i] = Some(first);
+            }
+            first = i + 1;
+        }
+    }
+
+    next
+}
+
+// Where two segments of a path meet, a hit at the shared endpoint is found at the end of the
+// first one and at the start of the next. The end one is dropped when the start one was found,
+// on the segments given by `segment` and `t` for either side.
+fn drop_shared_endpoints(
+    intersections: &mut Vec<LyonIntersection>,
+    next: &[Option<usize>],
+    key: impl Fn(&LyonIntersection, u32) -> (u32, u32),
+    t: impl Fn(&LyonIntersection) -> f32,
+    segment: impl Fn(&LyonIntersection) -> u32,
+) {
+    const EPSILON: f32 = 1.0e-4;
+
+    let starts: std::collections::HashSet<(u32, u32)> = intersections
+        .iter()
+        .filter(|hit| t(hit) <= EPSILON)
+        .map(|hit| key(hit, segment(hit)))
+        .collect();
+
+    intersections.retain(|hit| {
+        let next = next[segment(hit) as usize];
+        t(hit) < 1.0 - EPSILON || next.is_none_or(|next| !starts.contains(&key(hit, next as u32)))
+    });
+}
+
+fn intersection(position: Point, segment_a: usize, t_a: f32, segment_b: usize, t_b: f32) -> LyonIntersection {
+    LyonIntersection {
+        position: LyonPoint {
+            x: position.x,
+            y: position.y,
+        },
+        segment_a: segment_a as u32,
+        t_a,
+        segment_b: segment_b as u32,
+        t_b,
+    }
+}
+
+pub fn segments_intersections(a: &[Segment], b: &[Segment]) -> Vec<LyonIntersection> {
+    let mut intersections = Vec::new();
+
+    for (i, segment_a) in a.iter().enumerate() {
+        for (j, segment_b) in b.iter().enumerate() {
+            for (t_a, t_b) in segment_intersections_t(segment_a, segment_b) {
+                let position = segment_a.sample(t_a);
+                intersections.push(intersection(position, i, t_a, j, t_b));
+            }
+        }
+    }
+
+    // The segment on the other side is part of the key, so that a corner of one path on an
+    // edge of the other one is still found once per edge.
+    drop_shared_endpoints(
+        &mut intersections,
+        &next_segments(a),
+        |hit, segment| (segment, hit.segment_b),
+        |hit| hit.t_a,
+        |hit| hit.segment_a,
+    );
+    drop_shared_endpoints(
+        &mut intersections,
+        &next_segments(b),
+        |hit, segment| (hit.segment_a, segment),
+        |hit| hit.t_b,
+        |hit| hit.segment_b,
+    );
+
+    intersections
+}
+
+// `t_b` is the distance along the ray in units of `direction`, the hits are sorted by it.
+pub fn ray_intersections(segments: &[Segment], origin: Point, direction: Vector) -> Vec<LyonIntersection> {
+    let mut intersections = Vec::new();
+
+    if direction.square_length() == 0.0 {
+        return intersections;
+    }
+
+    let line = Line {
+        point: origin,
+        vector: direction,
+    };
+    let ray_t = |p: Point| (p - origin).dot(direction) / direction.square_length();
+
+    for (i, segment) in segments.iter().enumerate() {
+        let hits: Vec<f32> = match segment {
+            Segment::Line(line_segment) => {
+                let v = line_segment.to_vector();
+                let denom = direction.cross(v);
+                if denom == 0.0 {
+                    continue;
+                }
+                let t = (line_segment.from - origin).cross(direction) / denom;
+                if (0.0..=1.0).contains(&t) {
+                    vec![t]
+                } else {
+                    Vec::new()
+                }
+            }
+            Segment::Curve(curve) => curve.line_intersections_t(&line).to_vec(),
+        };
+
+        for t in hits {
+            let position = segment.sample(t);
+            let distance = ray_t(position);
+            if distance >= 0.0 {
+                intersections.push(intersection(position, i, t, 0, distance));
+            }
+        }
+    }
+
+    drop_shared_endpoints(
+        &mut intersections,
+        &next_segments(segments),
+        |_, segment| (segment, 0),
+        |hit| hit.t_a,
+        |hit| hit.segment_a,
+    );
+
+    intersections.sort_by(|a, b| a.t_b.partial_cmp(&b.t_b).unwrap_or(std::cmp::Ordering::Equal));
+
+    intersections
+}
+
+fn into_handle(mut intersections: Vec<LyonIntersection>) -> *mut Vec<LyonIntersection> {
+    intersections.shrink_to_fit();
+    Box::into_raw(Box::new(intersections))
+}
+
+// Sorted by segment and curve parameter on the first path.
+#[no_mangle]
+pub extern fn LyonPath_Intersections(a: *mut Path, b: *mut Path) -> *mut Vec<LyonIntersection> {
+    assert!(!a.is_null());
+    assert!(!b.is_null());
+    let (a, b) = unsafe { (&*a, &*b) };
+
+    let mut intersections = segments_intersections(&segments(a), &segments(b));
+    intersections.sort_by(|a, b| {
+        a.segment_a
+            .cmp(&b.segment_a)
+            .then(a.t_a.partial_cmp(&b.t_a).unwrap_or(std::cmp::Ordering::Equal))
+    });
+
+    into_handle(intersections)
+}
+
+#[no_mangle]
+pub extern fn LyonPath_RayIntersections(p: *mut Path, origin: LyonPoint, direction: LyonVector) -> *mut Vec<LyonIntersection> {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    into_handle(ray_intersections(&segments(path), origin.into(), direction.into()))
+}
+
+// `t_b` is the curve parameter on the line segment, the hits are sorted by it.
+#[no_mangle]
+pub extern fn LyonPath_SegmentIntersections(p: *mut Path, from: LyonPoint, to: LyonPoint) -> *mut Vec<LyonIntersection> {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    let line = Segment::Line(LineSegment {
+        from: from.into(),
+        to: to.into(),
+    });
+
+    let mut intersections = segments_intersections(&segments(path), &[line]);
+    intersections.sort_by(|a, b| a.t_b.partial_cmp(&b.t_b).unwrap_or(std::cmp::Ordering::Equal));
+
+    into_handle(intersections)
+}
+
+#[no_mangle]
+pub extern fn LyonIntersections_Length(p: *mut Vec<LyonIntersection>) -> u32 {
+    unsafe { (*p).len() as u32 }
+}
+
+#[no_mangle]
+pub extern fn LyonIntersections_Data(p: *mut Vec<LyonIntersection>) -> *const LyonIntersection {
+    unsafe { (*p).as_ptr() }
+}
+
+#[no_mangle]
+pub extern fn LyonFreeIntersections(p: *mut Vec<LyonIntersection>) {
+    unsafe { Box::from_raw(p) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(from: (f32, f32), to: (f32, f32)) -> Segment {
+        Segment::Line(LineSegment {
+            from: Point::new(from.0, from.1),
+            to: Point::new(to.0, to.1),
+        })
+    }
+
+    fn hits(intersections: &[LyonIntersection]) -> Vec<(u32, f32, u32, f32)> {
+        let mut hits: Vec<_> = intersections.iter().map(|h| (h.segment_a, h.t_a, h.segment_b, h.t_b)).collect();
+        hits.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        hits
+    }
+
+    #[test]
+    fn shared_endpoints_are_found_once() {
+        // An open polyline, and a vertical line through its middle corner.
+        let polyline = [line((0.0, 0.0), (5.0, 0.0)), line((5.0, 0.0), (10.0, 0.0))];
+        let cut = [line((5.0, -1.0), (5.0, 1.0))];
+        assert_eq!(hits(&segments_intersections(&polyline, &cut)), [(1, 0.0, 0, 0.5)]);
+
+        // Both paths have a corner on the same point.
+        let other = [line((5.0, -1.0), (5.0, 0.0)), line((5.0, 0.0), (6.0, 1.0))];
+        assert_eq!(hits(&segments_intersections(&polyline, &other)), [(1, 0.0, 1, 0.0)]);
+
+        // The ray goes through the corner where a closed square starts and ends, and through
+        // the opposite one.
+        let square = [
+            line((0.0, 0.0), (1.0, 0.0)),
+            line((1.0, 0.0), (1.0, 1.0)),
+            line((1.0, 1.0), (0.0, 1.0)),
+            line((0.0, 1.0), (0.0, 0.0)),
+        ];
+        let ray = ray_intersections(&square, Point::new(-1.0, -1.0), Vector::new(1.0, 1.0));
+        assert_eq!(hits(&ray), [(0, 0.0, 0, 1.0), (2, 0.0, 0, 2.0)]);
+    }
+
+    #[test]
+    fn separate_hits_at_the_same_place_are_kept() {
+        // The segments of a bow tie cross without sharing an endpoint there.
+        let bow_tie = [
+            line((0.0, 0.0), (2.0, 2.0)),
+            line((2.0, 2.0), (2.0, 0.0)),
+            line((2.0, 0.0), (0.0, 2.0)),
+            line((0.0, 2.0), (0.0, 0.0)),
+        ];
+        let cut = [line((0.0, 1.0), (2.0, 1.0))];
+        assert_eq!(
+            hits(&segments_intersections(&bow_tie, &cut)),
+            [(0, 0.5, 0, 0.5), (1, 0.5, 0, 1.0), (2, 0.5, 0, 0.5), (3, 0.5, 0, 0.0)]
+        );
+
+        // Two sub-paths that end on the same point.
+        let touching = [line((0.0, 0.0), (5.0, 0.0)), line((5.0, 1.0), (5.0, 0.0))];
+        let cut = [line((4.0, -1.0), (6.0, 1.0))];
+        assert_eq!(hits(&segments_intersections(&touching, &cut)), [(0, 1.0, 0, 0.5), (1, 1.0, 0, 0.5)]);
+    }
+}
@@ -1,9 +1,20 @@
 mod additional_geometry;
+mod clip;
+mod distance;
 mod geometry;
+mod hull;
+mod intersections;
+mod optimize;
+mod path;
+mod path_properties;
 mod pathbuilder;
+mod serialize;
+mod simplify;
 mod tessellate;
+mod topology;
 mod types;
 mod vertex;
+mod wireframe;
 
 #[no_mangle]
 pub extern fn LyonVersion() -> u32 {
@@ -0,0 +1,387 @@
+use std::collections::HashMap;
+
+use crate::geometry::{triangles, Geometry, GeometryIndex};
+use crate::topology::TOPOLOGY_INDEXED_TRIANGLE_LIST;
+use crate::vertex::Vertex;
+
+#[derive(Copy, Clone, Debug, Default)]
+#[repr(C)]
+pub struct LyonOptimizeStats {
+    pub vertices_before: u32,
+    pub vertices_after: u32,
+    // Average cache miss ratio: vertex cache misses per triangle.
+    pub acmr_before: f32,
+    pub acmr_after: f32,
+}
+
+// The post-transform cache that the ordering is optimized for, and that the stats simulate.
+const CACHE_SIZE: usize = 32;
+
+fn close(a: f32, b: f32, epsilon: f32) -> bool {
+    (a - b).abs() <= epsilon
+}
+
+fn can_weld(a: &Vertex, b: &Vertex, epsilon: f32) -> bool {
+    close(a.position[0], b.position[0], epsilon)
+        && close(a.position[1], b.position[1], epsilon)
+        && close(a.original_position[0], b.original_position[0], epsilon)
+        && close(a.original_position[1], b.original_position[1], epsilon)
+        && close(a.normal[0], b.normal[0], epsilon)
+        && close(a.normal[1], b.normal[1], epsilon)
+        && close(a.centerline_distance, b.centerline_distance, epsilon)
+        && close(a.path_distance, b.path_distance, epsilon)
+        && a.color == b.color
+        && a.primitive_type == b.primitive_type
+        && a.fill_ind == b.fill_ind
+        && a.shape_ind == b.shape_ind
+}
+
+// Maps each vertex to the first vertex it can be welded with. Positions are hashed on a grid
+// of `epsilon` sized cells, so only the neighbouring cells need to be compared.
+fn weld(vertices: &[Vertex], epsilon: f32) -> Vec<usize> {
+    let cell_size = epsilon.max(f32::EPSILON);
+    let cell = |v: &Vertex| {
+        (
+            (v.position[0] / cell_size).floor() as i64,
+            (v.position[1] / cell_size).floor() as i64,
+        )
+    };
+
+    let mut grid: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    let mut remap = Vec::with_capacity(vertices.len());
+
+    for (i, v) in vertices.iter().enumerate() {
+        let (x, y) = cell(v);
+
+        let mut target = None;
+        'search: for dx in -1..=1 {
+            for dy in -1..=1 {
+                if let Some(candidates) = grid.get(&(x + dx, y + dy)) {
+                    if let Some(&c) = candidates.iter().find(|&&c| can_weld(&vertices[c], v, epsilon)) {
+                        target = Some(c);
+                        break 'search;
+                    }
+                }
+            }
+        }
+
+        match target {
+            Some(c) => remap.push(c),
+            None => {
+                grid.entry((x, y)).or_default().push(i);
+                remap.push(i);
+            }
+        }
+    }
+
+    remap
+}
+
+// Cache misses per triangle with a FIFO cache.
+fn acmr(triangles: &[[usize; 3]]) -> f32 {
+    if triangles.is_empty() {
+        return 0.0;
+    }
+
+    let mut cache: std::collections::VecDeque<usize> = std::collections::VecDeque::new();
+    let mut misses = 0;
+    for t in triangles {
+        for &v in t {
+            if !cache.contains(&v) {
+                misses += 1;
+                cache.push_back(v);
+                if cache.len() > CACHE_SIZE {
+                    cache.pop_front();
+                }
+            }
+        }
+    }
+
+    misses as f32 / triangles.len() as f32
+}
+
+// Tom Forsyth's "Linear-Speed Vertex Cache Optimisation".
+fn vertex_score(cache_position: Option<usize>, remaining: usize) -> f32 {
+    if remaining == 0 {
+        return -1.0;
+    }
+
+    let mut score = match cache_position {
+        // The vertices of the last triangle are penalized, so that strips do not turn back.
+        Some(position) if position < 3 => 0.75,
+        Some(position) => {
+            let scale = 1.0 / (CACHE_SIZE - 3) as f32;
+            (1.0 - (position - 3) as f32 * scale).powf(1.5)
+        }
+        None => 0.0,
+    };
+
+    // Favours vertices with few triangles left, to finish them off.
+    score += 2.0 * (remaining as f32).powf(-0.5);
+
+    score
+}
+
+fn reorder_triangles(triangles: &[[usize; 3]], vertex_count: usize) -> Vec<[usize; 3]> {
+    let mut vertex_triangles: Vec<Vec<usize>> = vec![Vec::new(); vertex_count];
+    for (i, t) in triangles.iter().enumerate() {
+        for &v in t {
+            vertex_triangles[v].push(i);
+        }
+    }
+
+    let mut cache_position: Vec<Option<usize>> = vec![None; vertex_count];
+    let mut scores: Vec<f32> = vertex_triangles
+        .iter()
+        .map(|t| vertex_score(None, t.len()))
+        .collect();
+    let mut triangle_scores: Vec<f32> = triangles
+        .iter()
+        .map(|t| t.iter().map(|&v| scores[v]).sum())
+        .collect();
+
+    let mut added = vec![false; triangles.len()];
+    let mut cache: Vec<usize> = Vec::with_capacity(CACHE_SIZE + 3);
+    let mut result = Vec::with_capacity(triangles.len());
+    let mut best: Option<usize> = None;
+    let mut cursor = 0;
+
+    while result.len() < triangles.len() {
+        // Without a candidate from the cache, continues with the next triangle not added yet.
+        let next = match best {
+            Some(t) => t,
+            None => {
+                while added[cursor] {
+                    cursor += 1;
+                }
+                cursor
+            }
+        };
+
+        added[next] = true;
+        result.push(triangles[next]);
+
+        for &v in &triangles[next] {
+            vertex_triangles[v].retain(|&t| t != next);
+
+            if let Some(position) = cache.iter().position(|&c| c == v) {
+                cache.remove(position);
+            }
+            cache.insert(0, v);
+        }
+
+        for (position, &v) in cache.iter().enumerate() {
+            cache_position[v] = if position < CACHE_SIZE { Some(position) } else { None };
+        }
+        for &v in cache.iter().skip(CACHE_SIZE) {
+            scores[v] = vertex_score(None, vertex_triangles[v].len());
+        }
+        cache.truncate(CACHE_SIZE);
+
+        best = None;
+        let mut best_score = f32::MIN;
+        for &v in &cache {
+            scores[v] = vertex_score(cache_position[v], vertex_triangles[v].len());
+        }
+        for &v in &cache {
+            for &t in &vertex_triangles[v] {
+                triangle_scores[t] = triangles[t].iter().map(|&tv| scores[tv]).sum();
+                if triangle_scores[t] > best_score {
+                    best_score = triangle_scores[t];
+                    best = Some(t);
+                }
+            }
+        }
+    }
+
+    result
+}
+
+// Welds the vertices within `epsilon` of each other, if it is not negative, and removes the
+// triangles that collapse. Then reorders the triangles for the vertex cache, and the vertices
+// in the order they are first used. Fails if the geometry is not an indexed triangle list.
+pub fn optimize<IndexType: GeometryIndex>(
+    geometry: &mut Geometry<IndexType>,
+    weld_epsilon: f32,
+) -> Option<LyonOptimizeStats> {
+    if geometry.topology != TOPOLOGY_INDEXED_TRIANGLE_LIST || geometry.buffers.indices.is_empty() {
+        return None;
+    }
+
+    let mut triangles = triangles(geometry);
+    let geometry = &mut geometry.buffers;
+    let mut stats = LyonOptimizeStats {
+        vertices_before: geometry.vertices.len() as u32,
+        acmr_before: acmr(&triangles),
+        ..Default::default()
+    };
+
+    if weld_epsilon >= 0.0 {
+        let remap = weld(&geometry.vertices, weld_epsilon);
+        triangles = triangles
+            .iter()
+            .map(|t| [remap[t[0]], remap[t[1]], remap[t[2]]])
+            .filter(|t| t[0] != t[1] && t[1] != t[2] && t[2] != t[0])
+            .collect();
+    }
+
+    let triangles = reorder_triangles(&triangles, geometry.vertices.len());
+
+    let mut new_index = vec![usize::MAX; geometry.vertices.len()];
+    let mut vertices = Vec::with_capacity(geometry.vertices.len());
+    let mut indices = Vec::with_capacity(triangles.len() * 3);
+    for t in &triangles {
+        for &v in t {
+            if new_index[v] == usize::MAX {
+                new_index[v] = vertices.len();
+                vertices.push(geometry.vertices[v]);
+            }
+
+            // Never more vertices than before, so the index fits.
+            indices.push(IndexType::from_usize(new_index[v]).unwrap());
+        }
+    }
+
+    let reordered: Vec<[usize; 3]> = triangles
+        .iter()
+        .map(|t| [new_index[t[0]], new_index[t[1]], new_index[t[2]]])
+        .collect();
+
+    stats.vertices_after = vertices.len() as u32;
+    stats.acmr_after = acmr(&reordered);
+
+    geometry.vertices = vertices;
+    geometry.indices = indices;
+
+    Some(stats)
+}
+
+fn optimize_with_stats<IndexType: GeometryIndex>(
+    p: *mut Geometry<IndexType>,
+    weld_epsilon: f32,
+    stats: *mut LyonOptimizeStats,
+) -> i32 {
+    assert!(!p.is_null());
+
+    match optimize(unsafe { &mut *p }, weld_epsilon) {
+        Some(result) => {
+            if !stats.is_null() {
+                unsafe { *stats = result };
+            }
+            1
+        }
+        None => 0,
+    }
+}
+
+#[no_mangle]
+pub extern fn LyonGeometry16_Optimize(p: *mut Geometry<u16>, weld_epsilon: f32, stats: *mut LyonOptimizeStats) -> i32 {
+    optimize_with_stats(p, weld_epsilon, stats)
+}
+
+#[no_mangle]
+pub extern fn LyonGeometry32_Optimize(p: *mut Geometry<u32>, weld_epsilon: f32, stats: *mut LyonOptimizeStats) -> i32 {
+    optimize_with_stats(p, weld_epsilon, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::TOPOLOGY_TRIANGLE_STRIP;
+    use lyon::tessellation::VertexBuffers;
+
+    // The triangles by the positions of their corners, each rotated to start at its smallest
+    // corner, so that the same triangles with the same winding give the same sorted list.
+    fn positions(geometry: &Geometry<u32>) -> Vec<[(u32, u32); 3]> {
+        let vertices = &geometry.buffers.vertices;
+        let mut result: Vec<[(u32, u32); 3]> = triangles(geometry)
+            .iter()
+            .map(|t| {
+                let p = t.map(|i| (vertices[i].position[0].to_bits(), vertices[i].position[1].to_bits()));
+                let first = (0..3).min_by_key(|&k| p[k]).unwrap();
+                [p[first], p[(first + 1) % 3], p[(first + 2) % 3]]
+            })
+            .collect();
+        result.sort();
+
+        result
+    }
+
+    // A grid of quads split in two triangles, with the triangles in a scattered order. With
+    // `shared`, neighbouring triangles share their vertices, and otherwise each triangle has
+    // its own.
+    fn grid(size: usize, shared: bool) -> Geometry<u32> {
+        let mut quads = Vec::new();
+        for y in 0..size {
+            for x in 0..size {
+                let corner = |dx: usize, dy: usize| ((x + dx) as f32, (y + dy) as f32);
+                let (a, b, c, d) = (corner(0, 0), corner(1, 0), corner(1, 1), corner(0, 1));
+                quads.push([a, b, c]);
+                quads.push([a, c, d]);
+            }
+        }
+
+        let mut buffers: VertexBuffers<Vertex, u32> = VertexBuffers::new();
+        let mut index_of: HashMap<(u32, u32), u32> = HashMap::new();
+        for k in 0..quads.len() {
+            // 7 and the triangle count are coprime, so each triangle comes once.
+            for &(x, y) in &quads[k * 7 % quads.len()] {
+                let key = (x.to_bits(), y.to_bits());
+                let index = match index_of.get(&key) {
+                    Some(&index) if shared => index,
+                    _ => {
+                        buffers.vertices.push(Vertex::at(x, y));
+                        (buffers.vertices.len() - 1) as u32
+                    }
+                };
+                index_of.insert(key, index);
+                buffers.indices.push(index);
+            }
+        }
+
+        Geometry { buffers, topology: TOPOLOGY_INDEXED_TRIANGLE_LIST }
+    }
+
+    #[test]
+    fn reordering_keeps_the_triangles() {
+        let mut geometry = grid(8, true);
+        let before = positions(&geometry);
+
+        let stats = optimize(&mut geometry, -1.0).unwrap();
+        assert_eq!(positions(&geometry), before);
+        assert_eq!(stats.vertices_before, stats.vertices_after);
+        assert!(stats.acmr_after < stats.acmr_before);
+    }
+
+    #[test]
+    fn welding_keeps_the_triangles() {
+        let mut geometry = grid(4, false);
+        let before = positions(&geometry);
+        assert_eq!(geometry.buffers.vertices.len(), 4 * 4 * 6);
+
+        let stats = optimize(&mut geometry, 0.0).unwrap();
+        assert_eq!(positions(&geometry), before);
+        assert_eq!(stats.vertices_after, 5 * 5);
+        assert_eq!(geometry.buffers.vertices.len(), 5 * 5);
+    }
+
+    #[test]
+    fn cache_misses_per_triangle() {
+        assert_eq!(acmr(&[[0, 1, 2], [2, 1, 3]]), 2.0);
+        assert_eq!(acmr(&[[0, 1, 2], [0, 1, 2]]), 1.5);
+    }
+
+    #[test]
+    fn strips_are_not_optimized() {
+        let mut geometry = Geometry {
+            buffers: VertexBuffers {
+                vertices: (0..4).map(|i| Vertex::at(i as f32, (i % 2) as f32)).collect(),
+                indices: vec![0u32, 1, 2, 3, 2, 1],
+            },
+            topology: TOPOLOGY_TRIANGLE_STRIP,
+        };
+
+        assert!(optimize(&mut geometry, 0.0).is_none());
+        assert_eq!(geometry.buffers.indices, [0, 1, 2, 3, 2, 1]);
+    }
+}
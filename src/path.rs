@@ -0,0 +1,460 @@
+use std::ops::Range;
+
+use lyon::algorithms::aabb::bounding_box;
+use lyon::algorithms::area::approximate_signed_area;
+use lyon::algorithms::hit_test::path_winding_number_at_position;
+use lyon::algorithms::measure::{PathMeasurements, SampleType};
+use lyon::math::Point;
+use lyon::path::iterator::PathIterator;
+use lyon::path::path::BuilderImpl;
+use lyon::path::traits::Build;
+use lyon::path::{Path, PathEvent};
+use lyon::math::{Box2D, Transform};
+use lyon::tessellation::{FillOptions, LineCap, LineJoin};
+
+use crate::simplify;
+use crate::tessellate::{stroke_options, CStrokeOptions};
+use crate::types::{LyonRect, LyonTransform};
+
+// Same semantics as `CFillOptions::tolerance`, non-positive values use lyon's default.
+pub fn tolerance_or_default(tolerance: f32) -> f32 {
+    if tolerance > 0.0 {
+        tolerance
+    } else {
+        FillOptions::DEFAULT_TOLERANCE
+    }
+}
+
+// Splits the path into one path per sub-path.
+pub fn sub_paths(path: &Path) -> Vec<Path> {
+    let mut result = Vec::new();
+    let mut builder = Path::builder();
+
+    for event in path.iter() {
+        builder.path_event(event);
+
+        if let PathEvent::End { .. } = event {
+            result.push(builder.build());
+            builder = Path::builder();
+        }
+    }
+
+    result
+}
+
+pub struct Polyline {
+    pub points: Vec<Point>,
+    pub closed: bool,
+}
+
+// Approximates each sub-path with line segments, within the tolerance. The closing
+// point of closed sub-paths is not repeated.
+pub fn polylines(path: &Path, tolerance: f32) -> Vec<Polyline> {
+    let mut result = Vec::new();
+    let mut points = Vec::new();
+
+    for event in path.iter().flattened(tolerance) {
+        match event {
+            PathEvent::Begin { at } => {
+                points.push(at);
+            }
+            PathEvent::Line { to, .. } => {
+                points.push(to);
+            }
+            PathEvent::End { close, .. } => {
+                if close && points.len() > 1 && points.first() == points.last() {
+                    points.pop();
+                }
+
+                result.push(Polyline {
+                    points: std::mem::take(&mut points),
+                    closed: close,
+                });
+            }
+            PathEvent::Quadratic { .. } | PathEvent::Cubic { .. } => {
+                unreachable!("Unexpected curve in a flattened path");
+            }
+        }
+    }
+
+    result
+}
+
+// The part of the path within the range. Curves are split exactly, only the
+// measurements are approximated within the tolerance.
+pub fn trimmed(path: &Path, range: Range<f32>, sample_type: SampleType, tolerance: f32) -> Path {
+    let measurements = PathMeasurements::from_path(path, tolerance);
+    if measurements.length() <= 0.0 {
+        return Path::new();
+    }
+
+    let range = if range.start > range.end {
+        range.end..range.start
+    } else {
+        range
+    };
+
+    let mut builder = BuilderImpl::new();
+    let mut sampler = measurements.create_sampler(path, sample_type);
+    sampler.split_range(range, &mut builder);
+
+    builder.build()
+}
+
+pub fn reversed(path: &Path) -> Path {
+    let mut builder = Path::builder();
+    for event in path.reversed() {
+        builder.path_event(event);
+    }
+
+    builder.build()
+}
+
+// Positive when the sub-path is counter-clockwise with the y axis pointing up.
+pub fn signed_area(path: &Path, tolerance: f32) -> f32 {
+    approximate_signed_area(tolerance, path.iter())
+}
+
+fn sign(value: f32) -> i32 {
+    if value > 0.0 {
+        1
+    } else if value < 0.0 {
+        -1
+    } else {
+        0
+    }
+}
+
+// Whether most of the endpoints of `inner` are inside of `outer`.
+fn is_inside(inner: &Path, outer: &Path, tolerance: f32) -> bool {
+    let mut inside = 0;
+    let mut total = 0;
+    for event in inner.iter() {
+        if let PathEvent::Begin { at } | PathEvent::Line { to: at, .. } = event {
+            total += 1;
+            if path_winding_number_at_position(&at, outer.iter(), tolerance) != 0 {
+                inside += 1;
+            }
+        }
+    }
+
+    inside * 2 > total
+}
+
+// Outer sub-paths become counter-clockwise and holes clockwise, alternating with the nesting depth.
+pub fn normalized_winding(path: &Path, tolerance: f32) -> Path {
+    let sub_paths = sub_paths(path);
+
+    let mut builder = Path::builder();
+    for (i, sub_path) in sub_paths.iter().enumerate() {
+        let depth = sub_paths
+            .iter()
+            .enumerate()
+            .filter(|(j, other)| i != *j && is_inside(sub_path, other, tolerance))
+            .count();
+
+        let expected = if depth % 2 == 0 { 1 } else { -1 };
+        let actual = sign(signed_area(sub_path, tolerance));
+
+        if actual != 0 && actual != expected {
+            builder.extend_from_paths(&[reversed(sub_path).as_slice()]);
+        } else {
+            builder.extend_from_paths(&[sub_path.as_slice()]);
+        }
+    }
+
+    builder.build()
+}
+
+#[no_mangle]
+pub extern fn LyonPath_Clone(p: *mut Path) -> *mut Path {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    Box::into_raw(Box::new(path.clone()))
+}
+
+#[no_mangle]
+pub extern fn LyonPath_Concat(a: *mut Path, b: *mut Path) -> *mut Path {
+    assert!(!a.is_null());
+    assert!(!b.is_null());
+
+    let a = unsafe { &*a };
+    let b = unsafe { &*b };
+
+    let mut builder = Path::builder();
+    builder.extend_from_paths(&[a.as_slice(), b.as_slice()]);
+
+    Box::into_raw(Box::new(builder.build()))
+}
+
+#[no_mangle]
+pub extern fn LyonPath_SubPathCount(p: *mut Path) -> u32 {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    path.iter()
+        .filter(|event| matches!(event, PathEvent::Begin { .. }))
+        .count() as u32
+}
+
+// Returns null if there is no such sub-path.
+#[no_mangle]
+pub extern fn LyonPath_ExtractSubPath(p: *mut Path, index: u32) -> *mut Path {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    match sub_paths(path).into_iter().nth(index as usize) {
+        Some(sub_path) => Box::into_raw(Box::new(sub_path)),
+        None => std::ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub extern fn LyonPath_Reversed(p: *mut Path) -> *mut Path {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    Box::into_raw(Box::new(reversed(path)))
+}
+
+#[no_mangle]
+pub extern fn LyonPath_NormalizeWinding(p: *mut Path, tolerance: f32) -> *mut Path {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    let normalized = normalized_winding(path, tolerance_or_default(tolerance));
+
+    Box::into_raw(Box::new(normalized))
+}
+
+// Returns 0 if there is no such sub-path.
+#[no_mangle]
+pub extern fn LyonPath_SubPathSignedArea(p: *mut Path, index: u32, tolerance: f32) -> f32 {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    match sub_paths(path).get(index as usize) {
+        Some(sub_path) => signed_area(sub_path, tolerance_or_default(tolerance)),
+        None => 0.0,
+    }
+}
+
+#[no_mangle]
+pub extern fn LyonPath_SubPathWinding(p: *mut Path, index: u32, tolerance: f32) -> i32 {
+    sign(LyonPath_SubPathSignedArea(p, index, tolerance))
+}
+
+// Flattens the path, then drops every point closer than `epsilon` to the simplified outline.
+#[no_mangle]
+pub extern fn LyonPath_Simplify(p: *mut Path, epsilon: f32, tolerance: f32) -> *mut Path {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    let mut builder = Path::builder();
+    for polyline in polylines(path, tolerance_or_default(tolerance)) {
+        let mut points = polyline.points;
+        if polyline.closed {
+            points.push(points[0]);
+        }
+
+        let mut simplified = simplify::ramer_douglas_peucker(&points, epsilon.abs());
+        if polyline.closed && simplified.len() > 1 {
+            simplified.pop();
+        }
+
+        builder.add_polygon(lyon::path::Polygon {
+            points: &simplified,
+            closed: polyline.closed,
+        });
+    }
+
+    Box::into_raw(Box::new(builder.build()))
+}
+
+// Flattens the path, then replaces each sub-path with as few cubic curves as possible,
+// such that no flattened point is further than `max_error` from the curves.
+#[no_mangle]
+pub extern fn LyonPath_FitCurves(p: *mut Path, max_error: f32, tolerance: f32) -> *mut Path {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    let mut builder = Path::builder();
+    for polyline in polylines(path, tolerance_or_default(tolerance)) {
+        let mut points = polyline.points;
+        if polyline.closed {
+            points.push(points[0]);
+        }
+
+        let curves = simplify::fit_cubics(&points, max_error.abs());
+        if curves.is_empty() {
+            builder.begin(points[0]);
+            builder.end(polyline.closed);
+            continue;
+        }
+
+        builder.begin(curves[0].from);
+        for curve in curves {
+            builder.cubic_bezier_to(curve.ctrl1, curve.ctrl2, curve.to);
+        }
+        builder.end(polyline.closed);
+    }
+
+    Box::into_raw(Box::new(builder.build()))
+}
+
+#[no_mangle]
+pub extern fn LyonPath_Length(p: *mut Path, tolerance: f32) -> f32 {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    PathMeasurements::from_path(path, tolerance_or_default(tolerance)).length()
+}
+
+// `start` and `end` are fractions of the total length of the path.
+#[no_mangle]
+pub extern fn LyonPath_Trim(p: *mut Path, start: f32, end: f32, tolerance: f32) -> *mut Path {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    let trimmed = trimmed(
+        path,
+        start..end,
+        SampleType::Normalized,
+        tolerance_or_default(tolerance),
+    );
+
+    Box::into_raw(Box::new(trimmed))
+}
+
+// `start` and `end` are distances along the path.
+#[no_mangle]
+pub extern fn LyonPath_TrimDistance(p: *mut Path, start: f32, end: f32, tolerance: f32) -> *mut Path {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    let trimmed = trimmed(
+        path,
+        start..end,
+        SampleType::Distance,
+        tolerance_or_default(tolerance),
+    );
+
+    Box::into_raw(Box::new(trimmed))
+}
+
+// Splits the path in two at the given distance along it.
+#[no_mangle]
+pub extern fn LyonPath_SplitAtDistance(
+    p: *mut Path,
+    distance: f32,
+    tolerance: f32,
+    first: *mut *mut Path,
+    second: *mut *mut Path,
+) {
+    assert!(!p.is_null());
+    assert!(!first.is_null());
+    assert!(!second.is_null());
+    let path = unsafe { &*p };
+
+    let tolerance = tolerance_or_default(tolerance);
+    let before = trimmed(path, 0.0..distance, SampleType::Distance, tolerance);
+    let after = trimmed(path, distance..f32::MAX, SampleType::Distance, tolerance);
+
+    unsafe {
+        *first = Box::into_raw(Box::new(before));
+        *second = Box::into_raw(Box::new(after));
+    }
+}
+
+#[no_mangle]
+pub extern fn LyonPath_TransformedBoundingRect(p: *mut Path, transform: LyonTransform) -> LyonRect {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    let transform: Transform = transform.into();
+    bounding_box(path.iter().transformed(&transform)).into()
+}
+
+// Returns an empty rectangle if there is no such sub-path.
+#[no_mangle]
+pub extern fn LyonPath_SubPathBoundingRect(p: *mut Path, index: u32) -> LyonRect {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    match sub_paths(path).get(index as usize) {
+        Some(sub_path) => bounding_box(sub_path.iter()).into(),
+        None => Box2D::zero().into(),
+    }
+}
+
+// A conservative box around the stroke: the path's box grown by the furthest any join
+// or cap can reach from the centerline.
+#[no_mangle]
+pub extern fn LyonPath_StrokeBoundingRect(p: *mut Path, copts: CStrokeOptions) -> LyonRect {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    let rect = bounding_box(path.iter());
+    if copts.hairline != 0 || path.iter().next().is_none() {
+        return rect.into();
+    }
+
+    let opts = stroke_options(&copts);
+
+    let join_reach = match opts.line_join {
+        LineJoin::Miter | LineJoin::MiterClip => opts.miter_limit.max(1.0),
+        LineJoin::Round | LineJoin::Bevel => 1.0,
+    };
+
+    let cap_reach = |cap: LineCap| match cap {
+        LineCap::Square => std::f32::consts::SQRT_2,
+        LineCap::Butt | LineCap::Round => 1.0,
+    };
+
+    let reach = join_reach
+        .max(cap_reach(opts.start_cap))
+        .max(cap_reach(opts.end_cap));
+
+    rect.inflate(opts.line_width.abs() * 0.5 * reach, opts.line_width.abs() * 0.5 * reach)
+        .into()
+}
+
+// One polyline per sub-path, with the same tolerance semantics as `CFillOptions::tolerance`.
+#[no_mangle]
+pub extern fn LyonPath_Flatten(p: *mut Path, tolerance: f32) -> *mut Vec<Polyline> {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    Box::into_raw(Box::new(polylines(path, tolerance_or_default(tolerance))))
+}
+
+#[no_mangle]
+pub extern fn LyonPolylines_Count(p: *mut Vec<Polyline>) -> u32 {
+    unsafe { (*p).len() as u32 }
+}
+
+#[no_mangle]
+pub extern fn LyonPolylines_PointsLength(p: *mut Vec<Polyline>, index: u32) -> u32 {
+    let polylines = unsafe { &*p };
+    polylines.get(index as usize).map_or(0, |polyline| polyline.points.len() as u32)
+}
+
+#[no_mangle]
+pub extern fn LyonPolylines_PointsData(p: *mut Vec<Polyline>, index: u32) -> *const f32 {
+    let polylines = unsafe { &*p };
+    polylines
+        .get(index as usize)
+        .map_or(std::ptr::null(), |polyline| polyline.points.as_ptr() as *const f32)
+}
+
+#[no_mangle]
+pub extern fn LyonPolylines_IsClosed(p: *mut Vec<Polyline>, index: u32) -> i32 {
+    let polylines = unsafe { &*p };
+    polylines.get(index as usize).map_or(0, |polyline| polyline.closed as i32)
+}
+
+#[no_mangle]
+pub extern fn LyonFreePolylines(p: *mut Vec<Polyline>) {
+    unsafe { Box::from_raw(p) };
+}
@@ -0,0 +1,240 @@
+use lyon::algorithms::area::approximate_signed_area;
+use lyon::algorithms::measure::PathMeasurements;
+use lyon::geom::LineSegment;
+use lyon::math::{vector, Point};
+use lyon::path::Path;
+use lyon::tessellation::*;
+
+use crate::path::{polylines, sub_paths, tolerance_or_default};
+use crate::types::LyonPoint;
+
+// The triangles covering the filled area of the path.
+pub fn fill_triangles(path: &Path, fill_rule: i32, tolerance: f32) -> Vec<[Point; 3]> {
+    let mut opts = FillOptions::tolerance(tolerance);
+    if fill_rule != 0 {
+        opts.fill_rule = FillRule::NonZero
+    }
+
+    let mut geometry: VertexBuffers<Point, u32> = VertexBuffers::new();
+    let result = FillTessellator::new().tessellate_path(
+        path,
+        &opts,
+        &mut BuffersBuilder::new(&mut geometry, |v: FillVertex| v.position()),
+    );
+
+    if result.is_err() {
+        return Vec::new();
+    }
+
+    geometry
+        .indices
+        .chunks_exact(3)
+        .map(|t| {
+            [
+                geometry.vertices[t[0] as usize],
+                geometry.vertices[t[1] as usize],
+                geometry.vertices[t[2] as usize],
+            ]
+        })
+        .collect()
+}
+
+fn triangle_area(t: &[Point; 3]) -> f32 {
+    ((t[1] - t[0]).cross(t[2] - t[0]) * 0.5).abs()
+}
+
+// The edges of the flattened path, including the closing edges of closed sub-paths.
+pub fn flattened_edges(path: &Path, tolerance: f32) -> Vec<LineSegment<f32>> {
+    let mut edges = Vec::new();
+    for polyline in polylines(path, tolerance) {
+        let points = &polyline.points;
+        for pair in points.windows(2) {
+            edges.push(LineSegment {
+                from: pair[0],
+                to: pair[1],
+            });
+        }
+
+        if polyline.closed && points.len() > 2 {
+            edges.push(LineSegment {
+                from: points[points.len() - 1],
+                to: points[0],
+            });
+        }
+    }
+
+    edges
+}
+
+fn is_convex(points: &[Point]) -> bool {
+    // Flattening leaves nearly coincident and nearly collinear points around the
+    // curve endpoints, which would otherwise make the turn directions noisy.
+    const EPSILON: f32 = 1.0e-5;
+
+    let mut distinct: Vec<Point> = Vec::with_capacity(points.len());
+    for p in points {
+        if distinct.last().is_none_or(|last| (*p - *last).length() > EPSILON) {
+            distinct.push(*p);
+        }
+    }
+
+    while distinct.len() > 1 && (distinct[0] - distinct[distinct.len() - 1]).length() <= EPSILON {
+        distinct.pop();
+    }
+
+    let points = distinct;
+    if points.len() < 3 {
+        return false;
+    }
+
+    // All the turns must go the same way, and go around only once.
+    let mut sign = 0.0;
+    let mut total_turn = 0.0;
+    for i in 0..points.len() {
+        let a = points[i];
+        let b = points[(i + 1) % points.len()];
+        let c = points[(i + 2) % points.len()];
+
+        let incoming = b - a;
+        let outgoing = c - b;
+        let cross = incoming.cross(outgoing);
+        if cross.abs() <= EPSILON * incoming.length() * outgoing.length() {
+            continue;
+        }
+
+        if sign == 0.0 {
+            sign = cross.signum();
+        } else if cross.signum() != sign {
+            return false;
+        }
+
+        total_turn += incoming.angle_to(outgoing).radians;
+    }
+
+    // Polygons that wind around more than once turn by a multiple of 2 PI.
+    sign != 0.0 && total_turn.abs() < 3.0 * std::f32::consts::PI
+}
+
+// Sort and sweep along the x axis, so that only edges that overlap horizontally are tested.
+fn has_intersection(edges: &[LineSegment<f32>]) -> bool {
+    let mut sorted: Vec<(f32, f32, &LineSegment<f32>)> = edges
+        .iter()
+        .map(|e| (e.from.x.min(e.to.x), e.from.x.max(e.to.x), e))
+        .collect();
+    sorted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    for (i, (_, max_x, edge)) in sorted.iter().enumerate() {
+        for (min_x, _, other) in &sorted[i + 1..] {
+            if *min_x > *max_x {
+                break;
+            }
+
+            if edge.intersects(other) {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+// Sum of the signed areas of all sub-paths, positive for counter-clockwise sub-paths.
+#[no_mangle]
+pub extern fn LyonPath_SignedArea(p: *mut Path, tolerance: f32) -> f32 {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    approximate_signed_area(tolerance_or_default(tolerance), path.iter())
+}
+
+// The area that would be filled with the given fill rule.
+#[no_mangle]
+pub extern fn LyonPath_FilledArea(p: *mut Path, fill_rule: i32, tolerance: f32) -> f32 {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    fill_triangles(path, fill_rule, tolerance_or_default(tolerance))
+        .iter()
+        .map(triangle_area)
+        .sum()
+}
+
+// The centroid of the area that would be filled with the given fill rule.
+#[no_mangle]
+pub extern fn LyonPath_Centroid(p: *mut Path, fill_rule: i32, tolerance: f32) -> LyonPoint {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    let tolerance = tolerance_or_default(tolerance);
+
+    let mut area = 0.0;
+    let mut weighted = vector(0.0, 0.0);
+    for triangle in fill_triangles(path, fill_rule, tolerance) {
+        let a = triangle_area(&triangle);
+        let center = (triangle[0].to_vector() + triangle[1].to_vector() + triangle[2].to_vector()) / 3.0;
+
+        area += a;
+        weighted += center * a;
+    }
+
+    if area > 0.0 {
+        let c = weighted / area;
+        return LyonPoint { x: c.x, y: c.y };
+    }
+
+    // Nothing is filled, fall back to the average of the flattened points.
+    let points: Vec<Point> = polylines(path, tolerance)
+        .into_iter()
+        .flat_map(|polyline| polyline.points)
+        .collect();
+
+    if points.is_empty() {
+        return LyonPoint { x: 0.0, y: 0.0 };
+    }
+
+    let sum = points
+        .iter()
+        .fold(vector(0.0, 0.0), |sum, p| sum + p.to_vector());
+    let c = sum / points.len() as f32;
+
+    LyonPoint { x: c.x, y: c.y }
+}
+
+// Returns 0 if there is no such sub-path.
+#[no_mangle]
+pub extern fn LyonPath_SubPathLength(p: *mut Path, index: u32, tolerance: f32) -> f32 {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    match sub_paths(path).get(index as usize) {
+        Some(sub_path) => {
+            PathMeasurements::from_path(sub_path, tolerance_or_default(tolerance)).length()
+        }
+        None => 0.0,
+    }
+}
+
+// Sub-paths are considered closed. Returns 0 if there is no such sub-path.
+#[no_mangle]
+pub extern fn LyonPath_SubPathIsConvex(p: *mut Path, index: u32, tolerance: f32) -> i32 {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    let polylines = polylines(path, tolerance_or_default(tolerance));
+    match polylines.get(index as usize) {
+        Some(polyline) => is_convex(&polyline.points) as i32,
+        None => 0,
+    }
+}
+
+// Whether any two edges of the flattened path cross, within a sub-path or between sub-paths.
+// Edges that only touch at their endpoints, or overlap along a line, are not counted.
+#[no_mangle]
+pub extern fn LyonPath_IsSelfIntersecting(p: *mut Path, tolerance: f32) -> i32 {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    let edges = flattened_edges(path, tolerance_or_default(tolerance));
+
+    has_intersection(&edges) as i32
+}
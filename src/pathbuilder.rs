@@ -1,10 +1,11 @@
 use lyon::geom::*;
-use lyon::path::builder::SvgPathBuilder;
+use lyon::path::builder::{SvgPathBuilder, WithSvg};
 use lyon::path::math::{vector, Angle};
+use lyon::path::path::BuilderImpl;
 use lyon::path::Path;
 
 use crate::additional_geometry;
-use crate::types::{InternalBuilder, LyonPoint, LyonVector};
+use crate::types::{InternalBuilder, LyonBorderRadii, LyonPoint, LyonRect, LyonVector};
 
 // Path stuff
 #[no_mangle]
@@ -14,6 +15,18 @@ pub extern fn LyonCreatePathBuilder() -> *mut InternalBuilder {
     Box::into_raw(Box::new(svg))
 }
 
+// Creates a builder that starts with all the sub-paths of an existing path.
+#[no_mangle]
+pub extern fn LyonCreatePathBuilderFromPath(p: *mut Path) -> *mut InternalBuilder {
+    assert!(!p.is_null());
+    let path = unsafe { &*p };
+
+    let mut builder = BuilderImpl::new();
+    builder.extend_from_paths(&[path.as_slice()]);
+
+    Box::into_raw(Box::new(WithSvg::new(builder)))
+}
+
 #[no_mangle]
 pub extern fn LyonPathBuilder_MoveTo(p: *mut InternalBuilder, v: LyonPoint) {
     assert!(!p.is_null());
@@ -312,25 +325,367 @@ pub extern fn LyonPathBuilder_AddRoundedRect(
     additional_geometry::add_rounded_rectangle(p, min, max, border_radius);
 }
 
-#[derive(Copy, Clone, Debug)]
-#[repr(C)]
-pub struct LyonRect {
-    pub lower_left: [f32; 2],
-    pub upper_right: [f32; 2],
+#[no_mangle]
+pub extern fn LyonPathBuilder_AddRoundedRectRadii(
+    p: *mut InternalBuilder,
+    min: LyonPoint,
+    max: LyonPoint,
+    top_left: f32,
+    top_right: f32,
+    bottom_left: f32,
+    bottom_right: f32,
+) {
+    let circular = |r: f32| LyonVector { x: r, y: r };
+    let radii = LyonBorderRadii {
+        top_left: circular(top_left),
+        top_right: circular(top_right),
+        bottom_left: circular(bottom_left),
+        bottom_right: circular(bottom_right),
+    };
+
+    additional_geometry::add_rounded_rectangle_with_radii(p, min, max, radii);
+}
+
+#[no_mangle]
+pub extern fn LyonPathBuilder_AddRoundedRectElliptical(
+    p: *mut InternalBuilder,
+    min: LyonPoint,
+    max: LyonPoint,
+    radii: LyonBorderRadii,
+) {
+    additional_geometry::add_rounded_rectangle_with_radii(p, min, max, radii);
 }
 
+#[no_mangle]
+pub extern fn LyonPathBuilder_AddPolyline(
+    p: *mut InternalBuilder,
+    points: *const LyonPoint,
+    count: u32,
+    closed: i32,
+) {
+    LyonPathBuilder_AddPolylineStrided(
+        p,
+        points as *const u8,
+        std::mem::size_of::<LyonPoint>() as u32,
+        count,
+        closed,
+    );
+}
+
+#[no_mangle]
+pub extern fn LyonPathBuilder_AddPolygon(p: *mut InternalBuilder, points: *const LyonPoint, count: u32) {
+    LyonPathBuilder_AddPolyline(p, points, count, 1);
+}
+
+// Reads `count` points, each made of two consecutive floats, `stride` bytes apart. A stride
+// of 0 means tightly packed points, and nothing is added for other strides shorter than a point.
+#[no_mangle]
+pub extern fn LyonPathBuilder_AddPolylineStrided(
+    p: *mut InternalBuilder,
+    data: *const u8,
+    stride: u32,
+    count: u32,
+    closed: i32,
+) {
+    let point_size = std::mem::size_of::<LyonPoint>() as u32;
+    let stride = if stride == 0 { point_size } else { stride };
+
+    if count == 0 || stride < point_size {
+        return;
+    }
+
+    assert!(!data.is_null());
+
+    let points: Vec<_> = (0..count as usize)
+        .map(|i| {
+            let v = unsafe { (data.add(i * stride as usize) as *const LyonPoint).read_unaligned() };
+            v.into()
+        })
+        .collect();
+
+    additional_geometry::add_polyline(p, &points, closed != 0);
+}
+
+pub const VERB_MOVE_TO: u8 = 0;
+pub const VERB_LINE_TO: u8 = 1;
+pub const VERB_QUADRATIC_TO: u8 = 2;
+pub const VERB_CUBIC_TO: u8 = 3;
+pub const VERB_ARC: u8 = 4;
+pub const VERB_CLOSE: u8 = 5;
+
+// How many entries of the point array each verb consumes.
+fn points_per_verb(verb: u8) -> Option<usize> {
+    match verb {
+        VERB_MOVE_TO => Some(1),
+        VERB_LINE_TO => Some(1),
+        VERB_QUADRATIC_TO => Some(2),
+        VERB_CUBIC_TO => Some(3),
+        VERB_ARC => Some(3),
+        VERB_CLOSE => Some(0),
+        _ => None,
+    }
+}
+
+// Adds a whole path from a verb array and a point array. The stream is validated up front,
+// on error nothing is added to the builder and the error is returned in `output_err`.
+#[no_mangle]
+pub extern fn LyonPathBuilder_AddCommands(
+    p: *mut InternalBuilder,
+    verbs: *const u8,
+    verb_count: u32,
+    points: *const LyonPoint,
+    point_count: u32,
+    output_err: *mut *const i8,
+) {
+    assert!(!p.is_null());
+
+    if output_err.is_null() {
+        panic!("Null pointer err passed into AddCommands")
+    }
+
+    unsafe { *output_err = std::ptr::null_mut() };
+
+    let verbs = if verb_count == 0 {
+        &[]
+    } else {
+        assert!(!verbs.is_null());
+        unsafe { std::slice::from_raw_parts(verbs, verb_count as usize) }
+    };
+    let points = if point_count == 0 {
+        &[]
+    } else {
+        assert!(!points.is_null());
+        unsafe { std::slice::from_raw_parts(points, point_count as usize) }
+    };
+
+    let mut needed = 0;
+    for (i, verb) in verbs.iter().enumerate() {
+        let error = match points_per_verb(*verb) {
+            Some(count) => {
+                needed += count;
+                if needed > points.len() {
+                    Some(format!("Not enough points for verb {} at index {}", verb, i))
+                } else {
+                    None
+                }
+            }
+            None => Some(format!("Unknown verb {} at index {}", verb, i)),
+        };
+
+        if let Some(error) = error {
+            let err_str = std::ffi::CString::new(error).unwrap();
+            unsafe { *output_err = err_str.into_raw() };
+
+            return;
+        }
+    }
+
+    if needed != points.len() {
+        let error = format!("{} points given but the verbs use {}", points.len(), needed);
+        let err_str = std::ffi::CString::new(error).unwrap();
+        unsafe { *output_err = err_str.into_raw() };
+
+        return;
+    }
+
+    let builder = unsafe { &mut (*p) };
+
+    let mut points = points.iter().copied();
+    let mut next = || points.next().unwrap();
+    for verb in verbs {
+        match *verb {
+            VERB_MOVE_TO => {
+                builder.move_to(next().into());
+            }
+            VERB_LINE_TO => {
+                builder.line_to(next().into());
+            }
+            VERB_QUADRATIC_TO => {
+                let ctrl = next();
+                builder.quadratic_bezier_to(ctrl.into(), next().into());
+            }
+            VERB_CUBIC_TO => {
+                let ctrl1 = next();
+                let ctrl2 = next();
+                builder.cubic_bezier_to(ctrl1.into(), ctrl2.into(), next().into());
+            }
+            VERB_ARC => {
+                let center = next();
+                let radii = next();
+                let angles = next();
+                builder.arc(
+                    center.into(),
+                    vector(radii.x, radii.y),
+                    Angle::radians(angles.x),
+                    Angle::radians(angles.y),
+                );
+            }
+            VERB_CLOSE => {
+                builder.close();
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[no_mangle]
+pub extern fn LyonPathBuilder_AddRegularPolygon(
+    p: *mut InternalBuilder,
+    center: LyonPoint,
+    radius: f32,
+    sides: u32,
+    rotation: f32,
+) {
+    additional_geometry::add_regular_polygon(p, center, radius, sides, rotation);
+}
+
+#[no_mangle]
+pub extern fn LyonPathBuilder_AddStar(
+    p: *mut InternalBuilder,
+    center: LyonPoint,
+    outer_radius: f32,
+    inner_radius: f32,
+    branches: u32,
+    rotation: f32,
+) {
+    additional_geometry::add_star(p, center, outer_radius, inner_radius, branches, rotation);
+}
+
+#[no_mangle]
+pub extern fn LyonPathBuilder_AddPie(
+    p: *mut InternalBuilder,
+    center: LyonPoint,
+    radius: f32,
+    start_angle: f32,
+    sweep_angle: f32,
+) {
+    additional_geometry::add_pie(p, center, radius, start_angle, sweep_angle);
+}
+
+#[no_mangle]
+pub extern fn LyonPathBuilder_AddAnnularSector(
+    p: *mut InternalBuilder,
+    center: LyonPoint,
+    inner_radius: f32,
+    outer_radius: f32,
+    start_angle: f32,
+    sweep_angle: f32,
+) {
+    additional_geometry::add_annular_sector(
+        p,
+        center,
+        inner_radius,
+        outer_radius,
+        start_angle,
+        sweep_angle,
+    );
+}
+
+#[no_mangle]
+pub extern fn LyonPathBuilder_AddCapsule(
+    p: *mut InternalBuilder,
+    from: LyonPoint,
+    to: LyonPoint,
+    radius: f32,
+) {
+    additional_geometry::add_capsule(p, from, to, radius);
+}
+
+#[no_mangle]
+pub extern fn LyonPathBuilder_AddArrow(
+    p: *mut InternalBuilder,
+    from: LyonPoint,
+    to: LyonPoint,
+    shaft_width: f32,
+    head_width: f32,
+    head_length: f32,
+) {
+    additional_geometry::add_arrow(p, from, to, shaft_width, head_width, head_length);
+}
+
+// Tight: includes the extrema of the curves but not their control points.
 #[no_mangle]
 pub extern fn LyonPathBoundingRect(p: *mut Path) -> LyonRect {
     let path = unsafe { &mut (*p) };
     let rect = lyon::algorithms::aabb::bounding_box(path.iter());
 
-    LyonRect {
-        lower_left: rect.min.to_array(),
-        upper_right: (rect.max).to_array(),
-    }
+    rect.into()
 }
 
 #[no_mangle]
 pub extern fn LyonFreePath(p: *mut Path) {
     unsafe { Box::from_raw(p) };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lyon::path::PathEvent;
+
+    // Builds the path made by `add`, and returns its events.
+    fn events(add: impl FnOnce(*mut InternalBuilder)) -> Vec<PathEvent> {
+        let builder = LyonCreatePathBuilder();
+        add(builder);
+        let path = unsafe { Box::from_raw(LyonPathBuilder_Build(builder)) };
+        path.iter().collect()
+    }
+
+    #[test]
+    fn polyline_strides() {
+        // Points followed by a color.
+        let data: [f32; 9] = [0.0, 0.0, 1.0, 1.0, 0.0, 2.0, 1.0, 1.0, 3.0];
+        let packed = [LyonPoint { x: 0.0, y: 0.0 }, LyonPoint { x: 1.0, y: 0.0 }, LyonPoint { x: 1.0, y: 1.0 }];
+
+        let expected = events(|b| LyonPathBuilder_AddPolyline(b, packed.as_ptr(), 3, 1));
+        assert_eq!(expected.len(), 4);
+        let strided = events(|b| LyonPathBuilder_AddPolylineStrided(b, data.as_ptr() as *const u8, 12, 3, 1));
+        assert_eq!(strided, expected);
+        let tight = events(|b| LyonPathBuilder_AddPolylineStrided(b, packed.as_ptr() as *const u8, 0, 3, 1));
+        assert_eq!(tight, expected);
+
+        for stride in [1, 4, 7] {
+            let short = events(|b| LyonPathBuilder_AddPolylineStrided(b, data.as_ptr() as *const u8, stride, 3, 1));
+            assert!(short.is_empty());
+        }
+    }
+
+    // Adds the commands after a first segment, and returns the error if there is one.
+    fn add_commands(verbs: &[u8], points: &[LyonPoint]) -> (Vec<PathEvent>, Option<String>) {
+        let mut error = None;
+        let events = events(|b| {
+            LyonPathBuilder_AddPolyline(b, [LyonPoint { x: 0.0, y: 0.0 }, LyonPoint { x: 1.0, y: 0.0 }].as_ptr(), 2, 0);
+
+            let mut err = std::ptr::null();
+            let (verb_count, point_count) = (verbs.len() as u32, points.len() as u32);
+            LyonPathBuilder_AddCommands(b, verbs.as_ptr(), verb_count, points.as_ptr(), point_count, &mut err);
+            if !err.is_null() {
+                let err = unsafe { std::ffi::CString::from_raw(err as *mut i8) };
+                error = Some(err.into_string().unwrap());
+            }
+        });
+
+        (events, error)
+    }
+
+    #[test]
+    fn commands() {
+        let points: Vec<LyonPoint> = (0..5).map(|i| LyonPoint { x: i as f32, y: 5.0 }).collect();
+
+        let (events, error) = add_commands(&[VERB_MOVE_TO, VERB_LINE_TO, VERB_QUADRATIC_TO, VERB_CLOSE], &points[..4]);
+        assert_eq!(error, None);
+        assert_eq!(events.len(), 3 + 4);
+
+        let malformed: [(&[u8], usize, &str); 4] = [
+            (&[VERB_MOVE_TO, 9, VERB_LINE_TO], 2, "Unknown verb 9 at index 1"),
+            (&[VERB_MOVE_TO, VERB_CUBIC_TO], 3, "Not enough points for verb 3 at index 1"),
+            (&[VERB_MOVE_TO, VERB_LINE_TO], 3, "3 points given but the verbs use 2"),
+            (&[], 1, "1 points given but the verbs use 0"),
+        ];
+        for (verbs, point_count, expected) in malformed {
+            let (events, error) = add_commands(verbs, &points[..point_count]);
+            assert_eq!(error.as_deref(), Some(expected));
+            // Only the first segment.
+            assert_eq!(events.len(), 3);
+        }
+    }
+}
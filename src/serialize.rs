@@ -0,0 +1,387 @@
+use lyon::tessellation::VertexBuffers;
+
+use crate::geometry::{stats, Geometry, GeometryIndex};
+use crate::topology::{topology_or_default, TOPOLOGY_TRIANGLE_LIST, TOPOLOGY_TRIANGLE_STRIP};
+use crate::vertex::Vertex;
+
+// Blobs start with this, followed by the format version. All values are little-endian.
+const MAGIC: &[u8; 4] = b"CLYG";
+const VERSION: u32 = 1;
+
+const ATTRIBUTE_FLOAT: u32 = 0;
+const ATTRIBUTE_UINT: u32 = 1;
+const ATTRIBUTE_INT: u32 = 2;
+
+// The vertex layout: semantic, component type and component count of each attribute, in order.
+// Semantics follow the order of the fields of `Vertex`.
+const LAYOUT: [[u32; 3]; 9] = [
+    [0, ATTRIBUTE_FLOAT, 2],
+    [1, ATTRIBUTE_FLOAT, 2],
+    [2, ATTRIBUTE_FLOAT, 2],
+    [3, ATTRIBUTE_UINT, 1],
+    [4, ATTRIBUTE_UINT, 1],
+    [5, ATTRIBUTE_INT, 1],
+    [6, ATTRIBUTE_INT, 1],
+    [7, ATTRIBUTE_FLOAT, 1],
+    [8, ATTRIBUTE_FLOAT, 1],
+];
+
+const VERTEX_STRIDE: u32 = 48;
+
+// The layout above has to be updated, and the version bumped, when `Vertex` changes.
+const _: () = assert!(std::mem::size_of::<Vertex>() == VERTEX_STRIDE as usize);
+
+fn put_u32(blob: &mut Vec<u8>, value: u32) {
+    blob.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_f32(blob: &mut Vec<u8>, value: f32) {
+    blob.extend_from_slice(&value.to_le_bytes());
+}
+
+pub fn serialize<IndexType: GeometryIndex>(geometry: &Geometry<IndexType>) -> Vec<u8> {
+    let index_size = std::mem::size_of::<IndexType>();
+    let stats = stats(geometry);
+    let topology = geometry.topology;
+    let geometry = &geometry.buffers;
+
+    let mut blob = Vec::with_capacity(
+        64 + geometry.vertices.len() * VERTEX_STRIDE as usize + geometry.indices.len() * index_size,
+    );
+
+    blob.extend_from_slice(MAGIC);
+    put_u32(&mut blob, VERSION);
+    put_u32(&mut blob, index_size as u32);
+    put_u32(&mut blob, topology as u32);
+    put_u32(&mut blob, VERTEX_STRIDE);
+    put_u32(&mut blob, LAYOUT.len() as u32);
+    for attribute in LAYOUT.iter() {
+        for &value in attribute {
+            put_u32(&mut blob, value);
+        }
+    }
+    put_u32(&mut blob, geometry.vertices.len() as u32);
+    put_u32(&mut blob, geometry.indices.len() as u32);
+    for &value in stats.bounds.lower_left.iter().chain(stats.bounds.upper_right.iter()) {
+        put_f32(&mut blob, value);
+    }
+
+    for v in &geometry.vertices {
+        for &value in v.position.iter().chain(&v.original_position).chain(&v.normal) {
+            put_f32(&mut blob, value);
+        }
+        put_u32(&mut blob, v.color);
+        put_u32(&mut blob, v.primitive_type);
+        blob.extend_from_slice(&v.fill_ind.to_le_bytes());
+        blob.extend_from_slice(&v.shape_ind.to_le_bytes());
+        put_f32(&mut blob, v.centerline_distance);
+        put_f32(&mut blob, v.path_distance);
+    }
+
+    for &index in &geometry.indices {
+        let index = index.to_usize() as u32;
+        if index_size == 2 {
+            blob.extend_from_slice(&(index as u16).to_le_bytes());
+        } else {
+            put_u32(&mut blob, index);
+        }
+    }
+
+    blob
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], String> {
+        if self.data.len() - self.position < count {
+            return Err("Truncated geometry data".to_string());
+        }
+
+        let bytes = &self.data[self.position..self.position + count];
+        self.position += count;
+
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Result<i32, String> {
+        Ok(i32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Result<f32, String> {
+        Ok(f32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn f32x2(&mut self) -> Result<[f32; 2], String> {
+        Ok([self.f32()?, self.f32()?])
+    }
+}
+
+// Blobs of either index size can be loaded into geometry of either index size, as long as
+// the indices fit.
+pub fn deserialize<IndexType: GeometryIndex>(data: &[u8]) -> Result<Geometry<IndexType>, String> {
+    let mut reader = Reader { data, position: 0 };
+
+    if reader.bytes(4).ok() != Some(&MAGIC[..]) {
+        return Err("Not a serialized geometry".to_string());
+    }
+
+    let version = reader.u32()?;
+    if version != VERSION {
+        return Err(format!("Unsupported geometry version {}", version));
+    }
+
+    let index_size = reader.u32()?;
+    if index_size != 2 && index_size != 4 {
+        return Err(format!("Unsupported index size {}", index_size));
+    }
+
+    let topology = reader.u32()? as i32;
+    if topology_or_default(topology) != topology {
+        return Err(format!("Unsupported topology {}", topology));
+    }
+
+    let stride = reader.u32()?;
+    let attribute_count = reader.u32()? as usize;
+    if stride != VERTEX_STRIDE || attribute_count != LAYOUT.len() {
+        return Err("Unsupported vertex layout".to_string());
+    }
+    for attribute in LAYOUT.iter() {
+        for &value in attribute {
+            if reader.u32()? != value {
+                return Err("Unsupported vertex layout".to_string());
+            }
+        }
+    }
+
+    let vertex_count = reader.u32()? as usize;
+    let index_count = reader.u32()? as usize;
+
+    let complete = match topology {
+        TOPOLOGY_TRIANGLE_LIST => index_count == 0 && vertex_count % 3 == 0,
+        TOPOLOGY_TRIANGLE_STRIP => true,
+        _ => index_count % 3 == 0,
+    };
+    if !complete {
+        return Err("The counts do not match the topology".to_string());
+    }
+
+    // The bounds are only there for tools that read the header.
+    reader.bytes(16)?;
+
+    // Counts so large that the sizes overflow can't be backed by the data either.
+    let expected = vertex_count
+        .checked_mul(stride as usize)
+        .zip(index_count.checked_mul(index_size as usize))
+        .and_then(|(vertices, indices)| vertices.checked_add(indices));
+    if expected.is_none_or(|expected| data.len() - reader.position < expected) {
+        return Err("Truncated geometry data".to_string());
+    }
+
+    if index_count > 0 && vertex_count > IndexType::RESTART.to_usize() {
+        return Err("Too many vertices for the index size".to_string());
+    }
+
+    let mut geometry: VertexBuffers<Vertex, IndexType> = VertexBuffers::with_capacity(vertex_count, index_count);
+
+    for _ in 0..vertex_count {
+        geometry.vertices.push(Vertex {
+            position: reader.f32x2()?,
+            original_position: reader.f32x2()?,
+            normal: reader.f32x2()?,
+            color: reader.u32()?,
+            primitive_type: reader.u32()?,
+            fill_ind: reader.i32()?,
+            shape_ind: reader.i32()?,
+            centerline_distance: reader.f32()?,
+            path_distance: reader.f32()?,
+        });
+    }
+
+    let restart = if index_size == 2 { u16::MAX as u32 } else { u32::MAX };
+    for _ in 0..index_count {
+        let index = if index_size == 2 { reader.u16()? as u32 } else { reader.u32()? };
+
+        if index == restart && topology == TOPOLOGY_TRIANGLE_STRIP {
+            geometry.indices.push(IndexType::RESTART);
+        } else if (index as usize) < vertex_count {
+            geometry.indices.push(IndexType::from_usize(index as usize).unwrap());
+        } else {
+            return Err(format!("Index {} out of range", index));
+        }
+    }
+
+    Ok(Geometry { buffers: geometry, topology })
+}
+
+fn deserialize_or_error<IndexType: GeometryIndex>(
+    data: *const u8,
+    length: u32,
+    output_err: *mut *const i8
+) -> *mut Geometry<IndexType> {
+    if output_err.is_null() {
+        panic!("Null pointer err passed into DeserializeGeometry")
+    }
+
+    unsafe { *output_err = std::ptr::null_mut() };
+
+    let data = if data.is_null() {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(data, length as usize) }
+    };
+
+    match deserialize(data) {
+        Ok(geometry) => Box::into_raw(Box::new(geometry)),
+        Err(err) => {
+            let err_str = std::ffi::CString::new(err).unwrap();
+            unsafe { *output_err = err_str.into_raw() };
+
+            std::ptr::null_mut()
+        }
+    }
+}
+
+// Null if the length of the blob does not fit in the u32 that C gets, and loading takes.
+fn blob_or_null(blob: Vec<u8>) -> *mut Vec<u8> {
+    if u32::try_from(blob.len()).is_err() {
+        return std::ptr::null_mut();
+    }
+
+    Box::into_raw(Box::new(blob))
+}
+
+#[no_mangle]
+pub extern fn LyonGeometry16_Serialize(p: *mut Geometry<u16>) -> *mut Vec<u8> {
+    assert!(!p.is_null());
+    blob_or_null(serialize(unsafe { &*p }))
+}
+
+#[no_mangle]
+pub extern fn LyonGeometry32_Serialize(p: *mut Geometry<u32>) -> *mut Vec<u8> {
+    assert!(!p.is_null());
+    blob_or_null(serialize(unsafe { &*p }))
+}
+
+#[no_mangle]
+pub extern fn LyonDeserializeGeometry16(
+    data: *const u8,
+    length: u32,
+    output_err: *mut *const i8
+) -> *mut Geometry<u16> {
+    deserialize_or_error(data, length, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonDeserializeGeometry32(
+    data: *const u8,
+    length: u32,
+    output_err: *mut *const i8
+) -> *mut Geometry<u32> {
+    deserialize_or_error(data, length, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonBlob_Data(p: *mut Vec<u8>) -> *const u8 {
+    unsafe { (*p).as_ptr() }
+}
+
+#[no_mangle]
+pub extern fn LyonBlob_Length(p: *mut Vec<u8>) -> u32 {
+    unsafe { (*p).len() as u32 }
+}
+
+#[no_mangle]
+pub extern fn LyonFreeBlob(p: *mut Vec<u8>) {
+    unsafe { Box::from_raw(p) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::TOPOLOGY_INDEXED_TRIANGLE_LIST;
+
+    // Two unit squares as strips.
+    fn strips() -> Geometry<u16> {
+        let mut vertices: Vec<Vertex> = (0..8).map(|i| Vertex::at((i / 2) as f32, (i % 2) as f32)).collect();
+        vertices[3].color = 0xff00ff00;
+        vertices[5].path_distance = 2.5;
+
+        Geometry {
+            buffers: VertexBuffers {
+                vertices,
+                indices: vec![0, 1, 2, 3, u16::RESTART, 4, 5, 6, 7],
+            },
+            topology: TOPOLOGY_TRIANGLE_STRIP,
+        }
+    }
+
+    #[test]
+    fn round_trip_through_both_index_sizes() {
+        let blob = serialize(&strips());
+
+        let wide: Geometry<u32> = deserialize(&blob).unwrap();
+        assert_eq!(wide.topology, TOPOLOGY_TRIANGLE_STRIP);
+        assert_eq!(wide.buffers.indices, [0, 1, 2, 3, u32::RESTART, 4, 5, 6, 7]);
+
+        let narrow: Geometry<u16> = deserialize(&serialize(&wide)).unwrap();
+        assert_eq!(serialize(&narrow), blob);
+    }
+
+    #[test]
+    fn truncated_blobs_are_rejected() {
+        let blob = serialize(&strips());
+        for length in [20, 100, blob.len() - 1] {
+            assert_eq!(deserialize::<u16>(&blob[..length]).err().unwrap(), "Truncated geometry data");
+        }
+    }
+
+    #[test]
+    fn other_data_is_rejected() {
+        let mut blob = serialize(&strips());
+        blob[0] = b'X';
+        assert_eq!(deserialize::<u16>(&blob).err().unwrap(), "Not a serialized geometry");
+    }
+
+    #[test]
+    fn out_of_range_indices_are_rejected() {
+        let mut blob = serialize(&strips());
+        let last = blob.len() - 2;
+        blob[last..].copy_from_slice(&8u16.to_le_bytes());
+        assert_eq!(deserialize::<u16>(&blob).err().unwrap(), "Index 8 out of range");
+    }
+
+    // The blob of `strips()`, with another topology in the header.
+    fn strips_as(topology: i32) -> Vec<u8> {
+        let mut blob = serialize(&strips());
+        blob[12..16].copy_from_slice(&(topology as u32).to_le_bytes());
+        blob
+    }
+
+    #[test]
+    fn restart_indices_only_in_strips() {
+        // 9 indices, which could be 3 triangles.
+        let blob = strips_as(TOPOLOGY_INDEXED_TRIANGLE_LIST);
+        assert_eq!(deserialize::<u16>(&blob).err().unwrap(), "Index 65535 out of range");
+    }
+
+    #[test]
+    fn counts_must_match_the_topology() {
+        let blob = strips_as(TOPOLOGY_TRIANGLE_LIST);
+        assert_eq!(deserialize::<u16>(&blob).err().unwrap(), "The counts do not match the topology");
+
+        let blob = strips_as(3);
+        assert_eq!(deserialize::<u16>(&blob).err().unwrap(), "Unsupported topology 3");
+    }
+}
@@ -0,0 +1,269 @@
+// Polyline simplification and curve fitting, for dense input such as freehand strokes.
+use lyon::geom::{CubicBezierSegment, LineSegment};
+use lyon::math::{Point, Vector};
+
+// Ramer–Douglas–Peucker: keeps the fewest points such that no dropped point is
+// further than `epsilon` from the simplified polyline.
+pub fn ramer_douglas_peucker(points: &[Point], epsilon: f32) -> Vec<Point> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = stack.pop() {
+        let chord = LineSegment {
+            from: points[first],
+            to: points[last],
+        };
+
+        let mut max_distance = 0.0;
+        let mut index = first;
+        for (i, p) in points.iter().enumerate().take(last).skip(first + 1) {
+            let distance = chord.distance_to_point(*p);
+            if distance > max_distance {
+                max_distance = distance;
+                index = i;
+            }
+        }
+
+        if max_distance > epsilon {
+            keep[index] = true;
+            stack.push((first, index));
+            stack.push((index, last));
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep.iter())
+        .filter(|(_, keep)| **keep)
+        .map(|(p, _)| *p)
+        .collect()
+}
+
+// Schneider's algorithm from Graphics Gems: fits a sequence of cubic Béziers through
+// the points, such that no point is further than `max_error` from the curves.
+pub fn fit_cubics(points: &[Point], max_error: f32) -> Vec<CubicBezierSegment<f32>> {
+    let mut deduplicated: Vec<Point> = Vec::with_capacity(points.len());
+    for p in points {
+        if deduplicated.last() != Some(p) {
+            deduplicated.push(*p);
+        }
+    }
+
+    let mut result = Vec::new();
+    if deduplicated.len() < 2 {
+        return result;
+    }
+
+    let n = deduplicated.len();
+    let left_tangent = (deduplicated[1] - deduplicated[0]).normalize();
+    let right_tangent = (deduplicated[n - 2] - deduplicated[n - 1]).normalize();
+
+    fit_cubic(
+        &deduplicated,
+        left_tangent,
+        right_tangent,
+        max_error * max_error,
+        &mut result,
+    );
+
+    result
+}
+
+fn fit_cubic(
+    points: &[Point],
+    left_tangent: Vector,
+    right_tangent: Vector,
+    max_square_error: f32,
+    output: &mut Vec<CubicBezierSegment<f32>>,
+) {
+    const MAX_ITERATIONS: u32 = 4;
+
+    let first = points[0];
+    let last = points[points.len() - 1];
+
+    if points.len() == 2 {
+        let d = (last - first).length() / 3.0;
+        output.push(CubicBezierSegment {
+            from: first,
+            ctrl1: first + left_tangent * d,
+            ctrl2: last + right_tangent * d,
+            to: last,
+        });
+        return;
+    }
+
+    let mut u = chord_length_parameterize(points);
+    let mut curve = generate_bezier(points, &u, left_tangent, right_tangent);
+    let (mut error, mut split) = max_square_error_of(points, &curve, &u);
+    if error < max_square_error {
+        output.push(curve);
+        return;
+    }
+
+    // Close enough that a better parameterization may do.
+    if error < max_square_error * 4.0 {
+        for _ in 0..MAX_ITERATIONS {
+            u = reparameterize(points, &u, &curve);
+            curve = generate_bezier(points, &u, left_tangent, right_tangent);
+            let (e, s) = max_square_error_of(points, &curve, &u);
+            error = e;
+            split = s;
+
+            if error < max_square_error {
+                output.push(curve);
+                return;
+            }
+        }
+    }
+
+    let center_tangent = center_tangent(points, split);
+    fit_cubic(
+        &points[..=split],
+        left_tangent,
+        center_tangent,
+        max_square_error,
+        output,
+    );
+    fit_cubic(
+        &points[split..],
+        -center_tangent,
+        right_tangent,
+        max_square_error,
+        output,
+    );
+}
+
+fn center_tangent(points: &[Point], center: usize) -> Vector {
+    let tangent = points[center - 1] - points[center + 1];
+    if tangent.square_length() > 0.0 {
+        return tangent.normalize();
+    }
+
+    // The points on either side coincide, use the perpendicular of the incoming direction.
+    let incoming = points[center - 1] - points[center];
+    Vector::new(-incoming.y, incoming.x).normalize()
+}
+
+fn chord_length_parameterize(points: &[Point]) -> Vec<f32> {
+    let mut u = Vec::with_capacity(points.len());
+    u.push(0.0);
+    for i in 1..points.len() {
+        u.push(u[i - 1] + (points[i] - points[i - 1]).length());
+    }
+
+    let total = u[u.len() - 1];
+    for v in u.iter_mut() {
+        *v /= total;
+    }
+
+    u
+}
+
+// Least squares fit of the control points along the fixed end tangents.
+fn generate_bezier(
+    points: &[Point],
+    u: &[f32],
+    left_tangent: Vector,
+    right_tangent: Vector,
+) -> CubicBezierSegment<f32> {
+    let first = points[0];
+    let last = points[points.len() - 1];
+
+    let mut c = [[0.0f32; 2]; 2];
+    let mut x = [0.0f32; 2];
+
+    for (p, t) in points.iter().zip(u.iter()) {
+        let mt = 1.0 - t;
+        let b0 = mt * mt * mt;
+        let b1 = 3.0 * t * mt * mt;
+        let b2 = 3.0 * t * t * mt;
+        let b3 = t * t * t;
+
+        let a0 = left_tangent * b1;
+        let a1 = right_tangent * b2;
+
+        c[0][0] += a0.dot(a0);
+        c[0][1] += a0.dot(a1);
+        c[1][0] = c[0][1];
+        c[1][1] += a1.dot(a1);
+
+        let tmp = p.to_vector() - (first.to_vector() * (b0 + b1) + last.to_vector() * (b2 + b3));
+        x[0] += a0.dot(tmp);
+        x[1] += a1.dot(tmp);
+    }
+
+    let det_c0_c1 = c[0][0] * c[1][1] - c[1][0] * c[0][1];
+    let det_c0_x = c[0][0] * x[1] - c[1][0] * x[0];
+    let det_x_c1 = x[0] * c[1][1] - x[1] * c[0][1];
+
+    let (alpha_l, alpha_r) = if det_c0_c1 == 0.0 {
+        (0.0, 0.0)
+    } else {
+        (det_x_c1 / det_c0_c1, det_c0_x / det_c0_c1)
+    };
+
+    // Fall back to the Wu/Barsky heuristic if the solution is degenerate.
+    let segment_length = (last - first).length();
+    let epsilon = 1.0e-6 * segment_length;
+    let (alpha_l, alpha_r) = if alpha_l < epsilon || alpha_r < epsilon {
+        (segment_length / 3.0, segment_length / 3.0)
+    } else {
+        (alpha_l, alpha_r)
+    };
+
+    CubicBezierSegment {
+        from: first,
+        ctrl1: first + left_tangent * alpha_l,
+        ctrl2: last + right_tangent * alpha_r,
+        to: last,
+    }
+}
+
+pub fn second_derivative(curve: &CubicBezierSegment<f32>, t: f32) -> Vector {
+    let a = curve.ctrl2 - curve.ctrl1 * 2.0 + curve.from.to_vector();
+    let b = curve.to - curve.ctrl2 * 2.0 + curve.ctrl1.to_vector();
+
+    (a * (1.0 - t) + b * t) * 6.0
+}
+
+// Newton-Raphson step towards the parameter of the closest point on the curve.
+fn reparameterize(points: &[Point], u: &[f32], curve: &CubicBezierSegment<f32>) -> Vec<f32> {
+    points
+        .iter()
+        .zip(u.iter())
+        .map(|(p, t)| {
+            let d = curve.sample(*t) - *p;
+            let d1 = curve.derivative(*t);
+            let d2 = second_derivative(curve, *t);
+
+            let numerator = d.dot(d1);
+            let denominator = d1.dot(d1) + d.dot(d2);
+            if denominator == 0.0 {
+                *t
+            } else {
+                (*t - numerator / denominator).clamp(0.0, 1.0)
+            }
+        })
+        .collect()
+}
+
+fn max_square_error_of(points: &[Point], curve: &CubicBezierSegment<f32>, u: &[f32]) -> (f32, usize) {
+    let mut max_error = 0.0;
+    let mut split = points.len() / 2;
+
+    for i in 1..points.len() - 1 {
+        let error = (curve.sample(u[i]) - points[i]).square_length();
+        if error >= max_error {
+            max_error = error;
+            split = i;
+        }
+    }
+
+    (max_error, split)
+}
@@ -1,8 +1,15 @@
+use crate::clip::{clip_geometry, Clip};
+use crate::geometry::{Geometry, GeometryIndex};
+use crate::path::tolerance_or_default;
+use crate::topology::{convert_topology, topology_or_default, TOPOLOGY_TRIANGLE_LIST};
+use crate::types::{LyonPoint, LyonRect};
 use crate::vertex::{Vertex, PRIMITIVE_TYPE_FILLED, PRIMITIVE_TYPE_STROKED};
 
-use lyon::path::Path;
+use lyon::math::{point, vector, Angle, Box2D, Point, Vector};
+use lyon::path::builder::BorderRadii;
+use lyon::path::{IdEvent, Path, Winding};
 use lyon::tessellation::*;
-use std::ops::Add;
+use std::cell::RefCell;
 
 #[repr(C)]
 pub struct CFillOptions {
@@ -13,6 +20,15 @@ pub struct CFillOptions {
     pub color: u32,
     pub fill_ind: i32,
     pub shape_ind: i32,
+
+    // When `clip` is non-zero the output is clipped to `clip_rect`, and when `clip_path`
+    // is not null to the area it fills with the non-zero rule.
+    pub clip: i32,
+    pub clip_rect: LyonRect,
+    pub clip_path: *const Path,
+
+    // One of the `TOPOLOGY_*` constants, 0 for indexed triangle lists.
+    pub topology: i32,
 }
 
 #[repr(C)]
@@ -27,18 +43,20 @@ pub struct CStrokeOptions {
     pub shape_ind: i32,
 
     pub tolerance: f32,
-}
 
-fn tesselate_fill<IndexType: Add + From<VertexId> + geometry_builder::MaxIndex>(
-    p: *mut Path,
-    copts: CFillOptions,
-    output_err: *mut *const i8
-) -> *mut VertexBuffers<Vertex, IndexType> {
-    assert!(!p.is_null());
+    // When non-zero, the stroke is tessellated as a hairline: every vertex is
+    // placed on the centerline and the vertex shader extrudes it along `normal`.
+    pub hairline: i32,
 
-    let path = unsafe { &*p };
-    let mut tesselator = FillTessellator::new();
+    // Same as `CFillOptions`. Hairlines are clipped along their centerline.
+    pub clip: i32,
+    pub clip_rect: LyonRect,
+    pub clip_path: *const Path,
+
+    pub topology: i32,
+}
 
+fn fill_options(copts: &CFillOptions) -> FillOptions {
     let mut opts = FillOptions::default();
     if copts.tolerance > 0.0 {
         opts.tolerance = copts.tolerance
@@ -52,49 +70,7 @@ fn tesselate_fill<IndexType: Add + From<VertexId> + geometry_builder::MaxIndex>(
         opts.sweep_orientation = Orientation::Horizontal
     }
 
-    let mut geometry: VertexBuffers<Vertex, IndexType> = VertexBuffers::new();
-    let result = tesselator
-        .tessellate_path(
-            path,
-            &opts,
-            &mut BuffersBuilder::new(&mut geometry, |v: FillVertex| {
-                let p = v.position();
-
-                Vertex {
-                    position: [p.x, p.y],
-                    original_position: [p.x, p.y],
-                    normal: [0.0, 0.0],
-                    color: copts.color,
-                    primitive_type: PRIMITIVE_TYPE_FILLED,
-                    fill_ind: copts.fill_ind,
-                    shape_ind: copts.shape_ind,
-                }
-            }),
-        );
-
-    match result {
-        Ok(_) => {
-            // Happy path, returns the pointer to the generated geometry.
-            return Box::into_raw(Box::new(geometry));
-        }, 
-        Err(TessellationError::GeometryBuilder(err)) => {            
-            if err == GeometryBuilderError::TooManyVertices {
-                // Too many vertices. This should be caught and handled somewhere else.
-                return std::ptr::null_mut();
-            } else {
-                let err_str = std::ffi::CString::new(err.to_string()).unwrap();
-                unsafe { *output_err  = err_str.into_raw() };
-
-                return std::ptr::null_mut();
-            }
-        }, 
-        Err(other) => {
-            let err_str = std::ffi::CString::new(other.to_string()).unwrap();
-            unsafe { *output_err  = err_str.into_raw() };
-
-            return std::ptr::null_mut();
-        }
-    };
+    opts
 }
 
 fn cap_from_integer(i: i32) -> LineCap {
@@ -116,24 +92,7 @@ fn join_from_integer(i: i32) -> LineJoin {
     }
 }
 
-fn tesselate_stroke<IndexType: Add + From<VertexId> + geometry_builder::MaxIndex>(
-    p: *mut Path,
-    copts: CStrokeOptions,
-    output_err: *mut *const i8
-) -> *mut VertexBuffers<Vertex, IndexType> {
-    if p.is_null() {
-        panic!("Null pointer path passed into TessellateStroke")
-    }
-
-    if output_err.is_null() {
-        panic!("Null pointer err passed into TesselateStroke")
-    }
-
-    unsafe { *output_err = std::ptr::null_mut() };
-
-    let path = unsafe { &*p };
-    let mut tesselator = StrokeTessellator::new();
-
+pub fn stroke_options(copts: &CStrokeOptions) -> StrokeOptions {
     let mut opts = StrokeOptions::default();
     opts.start_cap = cap_from_integer(copts.start_cap);
     opts.end_cap = cap_from_integer(copts.end_cap);
@@ -141,31 +100,178 @@ fn tesselate_stroke<IndexType: Add + From<VertexId> + geometry_builder::MaxIndex
     opts.line_width = copts.width;
     opts.tolerance = copts.tolerance;
 
-    let mut geometry: VertexBuffers<Vertex, IndexType> = VertexBuffers::new();
-    let result = tesselator
-        .tessellate_path(
-            path,
-            &opts,
-            &mut BuffersBuilder::new(&mut geometry, |v: StrokeVertex| {
-                let normal = v.normal();
-                let p = v.position();
-
-                Vertex {
-                    position: [p.x, p.y],
-                    original_position: [p.x, p.y],
-                    normal: [normal.x, normal.y],
-                    color: copts.color,
-                    primitive_type: PRIMITIVE_TYPE_STROKED,
-                    fill_ind: copts.fill_ind,
-                    shape_ind: copts.shape_ind,
-                }
-            }),
-        );
+    // Hairlines have no width in path space. Joins and caps are still generated
+    // for the given width (or a unit width if none is given), and the distances
+    // are normalized so that the shader can scale them by its own width.
+    if copts.hairline != 0 && opts.line_width <= 0.0 {
+        opts.line_width = 1.0;
+    }
+
+    opts
+}
+
+fn fill_vertex(copts: &CFillOptions, v: FillVertex) -> Vertex {
+    let p = v.position();
+
+    Vertex {
+        position: [p.x, p.y],
+        original_position: [p.x, p.y],
+        normal: [0.0, 0.0],
+        color: copts.color,
+        primitive_type: PRIMITIVE_TYPE_FILLED,
+        fill_ind: copts.fill_ind,
+        shape_ind: copts.shape_ind,
+        centerline_distance: 0.0,
+        path_distance: 0.0,
+    }
+}
+
+fn stroke_vertex(copts: &CStrokeOptions, v: StrokeVertex) -> Vertex {
+    let normal = v.normal();
+    let (p, half_width) = if copts.hairline != 0 {
+        (v.position_on_path(), 0.5)
+    } else {
+        (v.position(), v.line_width() * 0.5)
+    };
+
+    let centerline_distance = match v.side() {
+        Side::Positive => half_width,
+        Side::Negative => -half_width,
+    };
+
+    Vertex {
+        position: [p.x, p.y],
+        original_position: [p.x, p.y],
+        normal: [normal.x, normal.y],
+        color: copts.color,
+        primitive_type: PRIMITIVE_TYPE_STROKED,
+        fill_ind: copts.fill_ind,
+        shape_ind: copts.shape_ind,
+        centerline_distance,
+        path_distance: v.advancement(),
+    }
+}
+
+fn clip_from(enabled: i32, rect: &LyonRect, path: *const Path, tolerance: f32) -> Option<Clip> {
+    let rect = if enabled != 0 {
+        Some(Box2D::new(rect.lower_left.into(), rect.upper_right.into()))
+    } else {
+        None
+    };
+
+    Clip::new(rect, unsafe { path.as_ref() }, tolerance_or_default(tolerance))
+}
+
+// Clips what was appended to the geometry since `first_vertex` and `first_index`, then
+// converts it to the topology.
+fn finish_appended<IndexType: GeometryIndex>(
+    geometry: &mut VertexBuffers<Vertex, IndexType>,
+    first_vertex: usize,
+    first_index: usize,
+    clip: Option<Clip>,
+    topology: i32,
+) -> TessellationResult {
+    if let Some(clip) = clip {
+        clip_geometry(geometry, first_vertex, first_index, &clip)
+            .map_err(TessellationError::GeometryBuilder)?;
+    }
+
+    convert_topology(geometry, first_vertex, first_index, topology)
+        .map_err(TessellationError::GeometryBuilder)
+}
+
+// Appends the fill of the path to the geometry.
+fn fill_into<IndexType: GeometryIndex>(
+    path: &Path,
+    copts: &CFillOptions,
+    geometry: &mut VertexBuffers<Vertex, IndexType>,
+) -> TessellationResult {
+    let mut tesselator = FillTessellator::new();
+    let opts = fill_options(copts);
+
+    let (first_vertex, first_index) = (geometry.vertices.len(), geometry.indices.len());
+    tesselator.tessellate_path(
+        path,
+        &opts,
+        &mut BuffersBuilder::new(geometry, |v: FillVertex| fill_vertex(copts, v)),
+    )?;
+
+    let clip = clip_from(copts.clip, &copts.clip_rect, copts.clip_path, copts.tolerance);
+    finish_appended(geometry, first_vertex, first_index, clip, copts.topology)
+}
+
+// lyon's advancement runs on from one sub-path to the next. Each sub-path starts at the
+// smallest distance of its vertices, that of its first endpoint.
+fn restart_path_distances(vertices: &mut [Vertex], sub_paths: &[usize], sub_path_count: usize) {
+    let mut starts = vec![f32::INFINITY; sub_path_count];
+    for (vertex, &sub_path) in vertices.iter().zip(sub_paths) {
+        starts[sub_path] = starts[sub_path].min(vertex.path_distance);
+    }
+
+    for (vertex, &sub_path) in vertices.iter_mut().zip(sub_paths) {
+        vertex.path_distance -= starts[sub_path];
+    }
+}
+
+// Appends the stroke of the path to the geometry.
+fn stroke_into<IndexType: GeometryIndex>(
+    path: &Path,
+    copts: &CStrokeOptions,
+    geometry: &mut VertexBuffers<Vertex, IndexType>,
+) -> TessellationResult {
+    let mut tesselator = StrokeTessellator::new();
+    let opts = stroke_options(copts);
+
+    // Vertices know the endpoint they come from, and the sub-path is the last one that
+    // begins at or before it.
+    let starts: Vec<usize> = path
+        .id_iter()
+        .filter_map(|event| match event {
+            IdEvent::Begin { at } => Some(at.to_usize()),
+            _ => None,
+        })
+        .collect();
+    let sub_paths = RefCell::new(Vec::new());
+
+    let (first_vertex, first_index) = (geometry.vertices.len(), geometry.indices.len());
+    tesselator.tessellate_with_ids(
+        path.id_iter(),
+        path,
+        None,
+        &opts,
+        &mut BuffersBuilder::new(geometry, |v: StrokeVertex| {
+            let endpoint = match v.source() {
+                VertexSource::Endpoint { id } => id,
+                VertexSource::Edge { from, .. } => from,
+            };
+            sub_paths
+                .borrow_mut()
+                .push(starts.partition_point(|&start| start <= endpoint.to_usize()) - 1);
+
+            stroke_vertex(copts, v)
+        }),
+    )?;
+
+    restart_path_distances(&mut geometry.vertices[first_vertex..], &sub_paths.into_inner(), starts.len());
+
+    let clip = clip_from(copts.clip, &copts.clip_rect, copts.clip_path, copts.tolerance);
+    finish_appended(geometry, first_vertex, first_index, clip, copts.topology)
+}
 
+// Hands the geometry over to C, or reports the error and drops it.
+fn geometry_or_error<IndexType>(
+    result: TessellationResult,
+    geometry: VertexBuffers<Vertex, IndexType>,
+    topology: i32,
+    output_err: *mut *const i8
+) -> *mut Geometry<IndexType> {
     match result {
         Ok(_) => {
             // Happy path, returns the pointer to the generated geometry.
-            return Box::into_raw(Box::new(geometry));
+            return Box::into_raw(Box::new(Geometry {
+                buffers: geometry,
+                topology: topology_or_default(topology),
+            }));
         }, 
         Err(TessellationError::GeometryBuilder(err)) => {            
             if err == GeometryBuilderError::TooManyVertices {
@@ -187,12 +293,226 @@ fn tesselate_stroke<IndexType: Add + From<VertexId> + geometry_builder::MaxIndex
     };
 }
 
+fn tesselate_fill<IndexType: GeometryIndex>(
+    p: *mut Path,
+    copts: CFillOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<IndexType> {
+    assert!(!p.is_null());
+
+    let path = unsafe { &*p };
+
+    let mut geometry: VertexBuffers<Vertex, IndexType> = VertexBuffers::new();
+    let result = fill_into(path, &copts, &mut geometry);
+
+    geometry_or_error(result, geometry, copts.topology, output_err)
+}
+
+fn tesselate_stroke<IndexType: GeometryIndex>(
+    p: *mut Path,
+    copts: CStrokeOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<IndexType> {
+    if p.is_null() {
+        panic!("Null pointer path passed into TessellateStroke")
+    }
+
+    if output_err.is_null() {
+        panic!("Null pointer err passed into TesselateStroke")
+    }
+
+    unsafe { *output_err = std::ptr::null_mut() };
+
+    let path = unsafe { &*p };
+
+    let mut geometry: VertexBuffers<Vertex, IndexType> = VertexBuffers::new();
+    let result = stroke_into(path, &copts, &mut geometry);
+
+    geometry_or_error(result, geometry, copts.topology, output_err)
+}
+
+pub const SHAPE_ORDER_STROKE_FIRST: i32 = 1;
+
+#[derive(Copy, Clone, Debug, Default)]
+#[repr(C)]
+pub struct LyonShapeRanges {
+    pub fill_first_index: u32,
+    pub fill_index_count: u32,
+    pub stroke_first_index: u32,
+    pub stroke_index_count: u32,
+}
+
+// Ranges count vertices in unindexed triangle lists, and indices otherwise.
+fn output_len<IndexType>(geometry: &VertexBuffers<Vertex, IndexType>, topology: i32) -> u32 {
+    if topology == TOPOLOGY_TRIANGLE_LIST {
+        geometry.vertices.len() as u32
+    } else {
+        geometry.indices.len() as u32
+    }
+}
+
+fn tesselate_shape<IndexType: GeometryIndex>(
+    p: *mut Path,
+    fill: CFillOptions,
+    mut stroke: CStrokeOptions,
+    order: i32,
+    ranges: *mut LyonShapeRanges,
+    output_err: *mut *const i8
+) -> *mut Geometry<IndexType> {
+    if p.is_null() {
+        panic!("Null pointer path passed into TessellateShape")
+    }
+
+    if output_err.is_null() {
+        panic!("Null pointer err passed into TessellateShape")
+    }
+
+    unsafe { *output_err = std::ptr::null_mut() };
+
+    let path = unsafe { &*p };
+
+    // Both parts share the buffers, and so the topology of the fill.
+    stroke.topology = fill.topology;
+    let topology = fill.topology;
+
+    let mut shape_ranges = LyonShapeRanges::default();
+
+    let mut geometry: VertexBuffers<Vertex, IndexType> = VertexBuffers::new();
+    let result = if order == SHAPE_ORDER_STROKE_FIRST {
+        stroke_into(path, &stroke, &mut geometry).and_then(|_| {
+            shape_ranges.stroke_index_count = output_len(&geometry, topology);
+            shape_ranges.fill_first_index = output_len(&geometry, topology);
+
+            fill_into(path, &fill, &mut geometry)
+        })
+    } else {
+        fill_into(path, &fill, &mut geometry).and_then(|_| {
+            shape_ranges.fill_index_count = output_len(&geometry, topology);
+            shape_ranges.stroke_first_index = output_len(&geometry, topology);
+
+            stroke_into(path, &stroke, &mut geometry)
+        })
+    };
+
+    if order == SHAPE_ORDER_STROKE_FIRST {
+        shape_ranges.fill_index_count = output_len(&geometry, topology) - shape_ranges.fill_first_index;
+    } else {
+        shape_ranges.stroke_index_count = output_len(&geometry, topology) - shape_ranges.stroke_first_index;
+    }
+
+    if result.is_ok() && !ranges.is_null() {
+        unsafe { *ranges = shape_ranges };
+    }
+
+    geometry_or_error(result, geometry, topology, output_err)
+}
+
+// Shapes that can be tessellated without building a `Path` first. Lyon only has dedicated
+// routines for filled rectangles and circles, the rest is fed to its path builder.
+#[derive(Clone, Copy)]
+enum Primitive {
+    Rect(Box2D),
+    RoundedRect(Box2D, f32),
+    Circle(Point, f32),
+    Ellipse(Point, Vector, Angle),
+}
+
+fn rect_from_points(min: LyonPoint, max: LyonPoint) -> Box2D {
+    Box2D::new(point(min.x, min.y), point(max.x, max.y))
+}
+
+fn tesselate_fill_primitive<IndexType: GeometryIndex>(
+    primitive: Primitive,
+    copts: CFillOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<IndexType> {
+    if output_err.is_null() {
+        panic!("Null pointer err passed into TessellateFill")
+    }
+
+    unsafe { *output_err = std::ptr::null_mut() };
+
+    let mut tesselator = FillTessellator::new();
+    let opts = fill_options(&copts);
+
+    let mut geometry: VertexBuffers<Vertex, IndexType> = VertexBuffers::new();
+    let mut output = BuffersBuilder::new(&mut geometry, |v: FillVertex| fill_vertex(&copts, v));
+
+    let result = match primitive {
+        Primitive::Rect(rect) => tesselator.tessellate_rectangle(&rect, &opts, &mut output),
+        Primitive::RoundedRect(rect, radius) => {
+            let mut builder = tesselator.builder(&opts, &mut output);
+            builder.add_rounded_rectangle(&rect, &BorderRadii::new(radius), Winding::Positive);
+            builder.build()
+        }
+        Primitive::Circle(center, radius) => {
+            tesselator.tessellate_circle(center, radius, &opts, &mut output)
+        }
+        Primitive::Ellipse(center, radii, x_rotation) => tesselator.tessellate_ellipse(
+            center,
+            radii,
+            x_rotation,
+            Winding::Positive,
+            &opts,
+            &mut output,
+        ),
+    };
+
+    let clip = clip_from(copts.clip, &copts.clip_rect, copts.clip_path, copts.tolerance);
+    let result = result.and_then(|_| finish_appended(&mut geometry, 0, 0, clip, copts.topology));
+
+    geometry_or_error(result, geometry, copts.topology, output_err)
+}
+
+fn tesselate_stroke_primitive<IndexType: GeometryIndex>(
+    primitive: Primitive,
+    copts: CStrokeOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<IndexType> {
+    if output_err.is_null() {
+        panic!("Null pointer err passed into TesselateStroke")
+    }
+
+    unsafe { *output_err = std::ptr::null_mut() };
+
+    let mut tesselator = StrokeTessellator::new();
+    let opts = stroke_options(&copts);
+
+    let mut geometry: VertexBuffers<Vertex, IndexType> = VertexBuffers::new();
+    let mut output = BuffersBuilder::new(&mut geometry, |v: StrokeVertex| stroke_vertex(&copts, v));
+
+    let result = match primitive {
+        Primitive::Rect(rect) => tesselator.tessellate_rectangle(&rect, &opts, &mut output),
+        Primitive::RoundedRect(rect, radius) => {
+            let mut builder = tesselator.builder(&opts, &mut output);
+            builder.add_rounded_rectangle(&rect, &BorderRadii::new(radius), Winding::Positive);
+            builder.build()
+        }
+        Primitive::Circle(center, radius) => {
+            tesselator.tessellate_circle(center, radius, &opts, &mut output)
+        }
+        Primitive::Ellipse(center, radii, x_rotation) => tesselator.tessellate_ellipse(
+            center,
+            radii,
+            x_rotation,
+            Winding::Positive,
+            &opts,
+            &mut output,
+        ),
+    };
+
+    let clip = clip_from(copts.clip, &copts.clip_rect, copts.clip_path, copts.tolerance);
+    let result = result.and_then(|_| finish_appended(&mut geometry, 0, 0, clip, copts.topology));
+
+    geometry_or_error(result, geometry, copts.topology, output_err)
+}
+
 #[no_mangle]
 pub extern fn LyonTessellateFill16(
     p: *mut Path,
     copts: CFillOptions,
     output_err: *mut *const i8
-) -> *mut VertexBuffers<Vertex, u16> {
+) -> *mut Geometry<u16> {
     tesselate_fill(p, copts, output_err)
 }
 
@@ -201,7 +521,7 @@ pub extern fn LyonTessellateFill32(
     p: *mut Path,
     copts: CFillOptions,
     output_err: *mut *const i8
-) -> *mut VertexBuffers<Vertex, u32> {
+) -> *mut Geometry<u32> {
     tesselate_fill(p, copts, output_err)
 }
 
@@ -210,7 +530,7 @@ pub extern fn LyonTessellateStroke16(
     p: *mut Path,
     copts: CStrokeOptions,
     output_err: *mut *const i8
-) -> *mut VertexBuffers<Vertex, u16> {
+) -> *mut Geometry<u16> {
     tesselate_stroke(p, copts, output_err)
 }
 
@@ -219,16 +539,430 @@ pub extern fn LyonTessellateStroke32(
     p: *mut Path,
     copts: CStrokeOptions,
     output_err: *mut *const i8
-) -> *mut VertexBuffers<Vertex, u32> {
+) -> *mut Geometry<u32> {
     tesselate_stroke(p, copts, output_err)
 }
 
 #[no_mangle]
-pub extern fn LyonFreeGeometry16(p: *mut VertexBuffers<Vertex, u16>) {
+pub extern fn LyonTessellateShape16(
+    p: *mut Path,
+    fill: CFillOptions,
+    stroke: CStrokeOptions,
+    order: i32,
+    ranges: *mut LyonShapeRanges,
+    output_err: *mut *const i8
+) -> *mut Geometry<u16> {
+    tesselate_shape(p, fill, stroke, order, ranges, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateShape32(
+    p: *mut Path,
+    fill: CFillOptions,
+    stroke: CStrokeOptions,
+    order: i32,
+    ranges: *mut LyonShapeRanges,
+    output_err: *mut *const i8
+) -> *mut Geometry<u32> {
+    tesselate_shape(p, fill, stroke, order, ranges, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateFillRect16(
+    min: LyonPoint,
+    max: LyonPoint,
+    copts: CFillOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<u16> {
+    tesselate_fill_primitive(Primitive::Rect(rect_from_points(min, max)), copts, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateFillRoundedRect16(
+    min: LyonPoint,
+    max: LyonPoint,
+    border_radius: f32,
+    copts: CFillOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<u16> {
+    let rect = rect_from_points(min, max);
+    tesselate_fill_primitive(Primitive::RoundedRect(rect, border_radius.abs()), copts, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateFillCircle16(
+    center: LyonPoint,
+    radius: f32,
+    copts: CFillOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<u16> {
+    tesselate_fill_primitive(Primitive::Circle(center.into(), radius.abs()), copts, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateFillEllipse16(
+    center: LyonPoint,
+    r_x: f32,
+    r_y: f32,
+    x_rotation: f32,
+    copts: CFillOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<u16> {
+    let ellipse = Primitive::Ellipse(center.into(), vector(r_x, r_y), Angle::radians(x_rotation));
+    tesselate_fill_primitive(ellipse, copts, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateFillRect32(
+    min: LyonPoint,
+    max: LyonPoint,
+    copts: CFillOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<u32> {
+    tesselate_fill_primitive(Primitive::Rect(rect_from_points(min, max)), copts, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateFillRoundedRect32(
+    min: LyonPoint,
+    max: LyonPoint,
+    border_radius: f32,
+    copts: CFillOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<u32> {
+    let rect = rect_from_points(min, max);
+    tesselate_fill_primitive(Primitive::RoundedRect(rect, border_radius.abs()), copts, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateFillCircle32(
+    center: LyonPoint,
+    radius: f32,
+    copts: CFillOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<u32> {
+    tesselate_fill_primitive(Primitive::Circle(center.into(), radius.abs()), copts, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateFillEllipse32(
+    center: LyonPoint,
+    r_x: f32,
+    r_y: f32,
+    x_rotation: f32,
+    copts: CFillOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<u32> {
+    let ellipse = Primitive::Ellipse(center.into(), vector(r_x, r_y), Angle::radians(x_rotation));
+    tesselate_fill_primitive(ellipse, copts, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateStrokeRect16(
+    min: LyonPoint,
+    max: LyonPoint,
+    copts: CStrokeOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<u16> {
+    tesselate_stroke_primitive(Primitive::Rect(rect_from_points(min, max)), copts, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateStrokeRoundedRect16(
+    min: LyonPoint,
+    max: LyonPoint,
+    border_radius: f32,
+    copts: CStrokeOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<u16> {
+    let rect = rect_from_points(min, max);
+    tesselate_stroke_primitive(Primitive::RoundedRect(rect, border_radius.abs()), copts, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateStrokeCircle16(
+    center: LyonPoint,
+    radius: f32,
+    copts: CStrokeOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<u16> {
+    tesselate_stroke_primitive(Primitive::Circle(center.into(), radius.abs()), copts, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateStrokeEllipse16(
+    center: LyonPoint,
+    r_x: f32,
+    r_y: f32,
+    x_rotation: f32,
+    copts: CStrokeOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<u16> {
+    let ellipse = Primitive::Ellipse(center.into(), vector(r_x, r_y), Angle::radians(x_rotation));
+    tesselate_stroke_primitive(ellipse, copts, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateStrokeRect32(
+    min: LyonPoint,
+    max: LyonPoint,
+    copts: CStrokeOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<u32> {
+    tesselate_stroke_primitive(Primitive::Rect(rect_from_points(min, max)), copts, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateStrokeRoundedRect32(
+    min: LyonPoint,
+    max: LyonPoint,
+    border_radius: f32,
+    copts: CStrokeOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<u32> {
+    let rect = rect_from_points(min, max);
+    tesselate_stroke_primitive(Primitive::RoundedRect(rect, border_radius.abs()), copts, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateStrokeCircle32(
+    center: LyonPoint,
+    radius: f32,
+    copts: CStrokeOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<u32> {
+    tesselate_stroke_primitive(Primitive::Circle(center.into(), radius.abs()), copts, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonTessellateStrokeEllipse32(
+    center: LyonPoint,
+    r_x: f32,
+    r_y: f32,
+    x_rotation: f32,
+    copts: CStrokeOptions,
+    output_err: *mut *const i8
+) -> *mut Geometry<u32> {
+    let ellipse = Primitive::Ellipse(center.into(), vector(r_x, r_y), Angle::radians(x_rotation));
+    tesselate_stroke_primitive(ellipse, copts, output_err)
+}
+
+#[no_mangle]
+pub extern fn LyonFreeGeometry16(p: *mut Geometry<u16>) {
     unsafe { Box::from_raw(p) };
 }
 
 #[no_mangle]
-pub extern fn LyonFreeGeometry32(p: *mut VertexBuffers<Vertex, u32>) {
+pub extern fn LyonFreeGeometry32(p: *mut Geometry<u32>) {
     unsafe { Box::from_raw(p) };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lyon::path::Side;
+
+    pub fn stroke_copts(width: f32, hairline: bool) -> CStrokeOptions {
+        CStrokeOptions {
+            start_cap: 0,
+            end_cap: 0,
+            join: 0,
+            width,
+            color: 0,
+            fill_ind: 0,
+            shape_ind: 0,
+            tolerance: 0.1,
+            hairline: hairline as i32,
+            clip: 0,
+            clip_rect: Box2D::zero().into(),
+            clip_path: std::ptr::null(),
+            topology: 0,
+        }
+    }
+
+    // Strokes a horizontal segment, and compares each output vertex with the lyon vertex
+    // it was made from.
+    fn stroke_segment(hairline: bool) -> Vec<(Vertex, Point, f32, Side)> {
+        let mut builder = Path::builder();
+        builder.begin(point(0.0, 0.0));
+        builder.line_to(point(10.0, 0.0));
+        builder.end(false);
+        let path = builder.build();
+
+        let copts = stroke_copts(2.0, hairline);
+        let mut geometry: VertexBuffers<(Vertex, Point, f32, Side), u32> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(
+                &path,
+                &stroke_options(&copts),
+                &mut BuffersBuilder::new(&mut geometry, |v: StrokeVertex| {
+                    let (on_path, advancement, side) = (v.position_on_path(), v.advancement(), v.side());
+                    (stroke_vertex(&copts, v), on_path, advancement, side)
+                }),
+            )
+            .unwrap();
+
+        geometry.vertices
+    }
+
+    #[test]
+    fn stroke_vertex_distances() {
+        let vertices = stroke_segment(false);
+        assert!(!vertices.is_empty());
+
+        for (vertex, on_path, advancement, side) in vertices {
+            // Half the width, signed by the side of the path.
+            let expected = match side {
+                Side::Positive => 1.0,
+                Side::Negative => -1.0,
+            };
+            assert_eq!(vertex.centerline_distance, expected);
+            assert_eq!(vertex.path_distance, advancement);
+            assert_eq!(vertex.path_distance, on_path.x);
+
+            // The vertex is on the side of the normal, at half the width from the path.
+            let offset = point(vertex.position[0], vertex.position[1]) - on_path;
+            let normal = vector(vertex.normal[0], vertex.normal[1]);
+            assert!((offset - normal).length() < 1e-5);
+            assert_eq!(offset.y.signum(), normal.y.signum());
+        }
+
+        // Distances along the path restart at each sub-path, open or closed.
+        let mut builder = Path::builder();
+        for y in [0.0, 5.0] {
+            builder.begin(point(0.0, y));
+            builder.line_to(point(10.0, y));
+            builder.end(false);
+        }
+        builder.begin(point(20.0, 0.0));
+        builder.line_to(point(30.0, 0.0));
+        builder.line_to(point(30.0, 10.0));
+        builder.line_to(point(20.0, 10.0));
+        builder.end(true);
+        let path = builder.build();
+
+        let mut geometry: VertexBuffers<Vertex, u32> = VertexBuffers::new();
+        stroke_into(&path, &stroke_copts(2.0, true), &mut geometry).unwrap();
+
+        let (open, closed): (Vec<Vertex>, Vec<Vertex>) = geometry.vertices.iter().partition(|v| v.position[0] <= 10.0);
+        assert!(!open.is_empty() && !closed.is_empty());
+        for vertex in open {
+            assert!((vertex.path_distance - vertex.position[0]).abs() < 1e-4);
+        }
+        // Hairline vertices are on the corners of the square, 10 apart, and the first corner
+        // is also where the sub-path closes.
+        let corners = [[20.0, 0.0], [30.0, 0.0], [30.0, 10.0], [20.0, 10.0]];
+        for vertex in closed {
+            let corner = corners.iter().position(|&c| c == vertex.position).unwrap();
+            let distance = vertex.path_distance;
+            assert!((distance - corner as f32 * 10.0).abs() < 1e-4 || (corner == 0 && (distance - 40.0).abs() < 1e-4));
+        }
+    }
+
+    #[test]
+    fn hairline_vertices_are_on_the_path() {
+        for (vertex, on_path, advancement, side) in stroke_segment(true) {
+            assert_eq!(vertex.position, [on_path.x, on_path.y]);
+            assert_eq!(vertex.path_distance, advancement);
+
+            let expected = match side {
+                Side::Positive => 0.5,
+                Side::Negative => -0.5,
+            };
+            assert_eq!(vertex.centerline_distance, expected);
+            assert!((vector(vertex.normal[0], vertex.normal[1]).length() - 1.0).abs() < 1e-5);
+        }
+    }
+
+    fn fill_copts() -> CFillOptions {
+        CFillOptions {
+            tolerance: 0.1,
+            fill_rule: 0,
+            orientation: 0,
+            color: 0,
+            fill_ind: 0,
+            shape_ind: 0,
+            clip: 0,
+            clip_rect: Box2D::zero().into(),
+            clip_path: std::ptr::null(),
+            topology: 0,
+        }
+    }
+
+    fn take<IndexType>(geometry: *mut Geometry<IndexType>) -> Geometry<IndexType> {
+        assert!(!geometry.is_null());
+        *unsafe { Box::from_raw(geometry) }
+    }
+
+    #[test]
+    fn rounded_rect_radius_sign_is_ignored() {
+        let (min, max) = (LyonPoint { x: 0.0, y: 0.0 }, LyonPoint { x: 20.0, y: 10.0 });
+        let mut err = std::ptr::null();
+
+        let positive = take(LyonTessellateFillRoundedRect32(min, max, 3.0, fill_copts(), &mut err));
+        let negative = take(LyonTessellateFillRoundedRect32(min, max, -3.0, fill_copts(), &mut err));
+        assert_eq!(positive.buffers.indices, negative.buffers.indices);
+        let positions = |g: &Geometry<u32>| g.buffers.vertices.iter().map(|v| v.position).collect::<Vec<_>>();
+        assert_eq!(positions(&positive), positions(&negative));
+    }
+
+    // Times the primitive entry points against building the same shape as a path and
+    // tessellating that. Run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn primitives_against_paths() {
+        const RUNS: u32 = 2000;
+        let (min, max) = (LyonPoint { x: 0.0, y: 0.0 }, LyonPoint { x: 200.0, y: 100.0 });
+        let center = LyonPoint { x: 100.0, y: 50.0 };
+        let rect = rect_from_points(min, max);
+
+        let shapes = [
+            ("rect", Primitive::Rect(rect)),
+            ("rounded rect", Primitive::RoundedRect(rect, 20.0)),
+            ("circle", Primitive::Circle(center.into(), 50.0)),
+            ("ellipse", Primitive::Ellipse(center.into(), vector(100.0, 50.0), Angle::zero())),
+        ];
+
+        let mut err = std::ptr::null();
+        for (name, primitive) in shapes {
+            let time = |f: &mut dyn FnMut()| {
+                let start = std::time::Instant::now();
+                for _ in 0..RUNS {
+                    f();
+                }
+                start.elapsed() / RUNS
+            };
+            let path = || {
+                let mut builder = Path::builder();
+                match primitive {
+                    Primitive::Rect(rect) => builder.add_rectangle(&rect, Winding::Positive),
+                    Primitive::RoundedRect(rect, radius) => {
+                        builder.add_rounded_rectangle(&rect, &BorderRadii::new(radius), Winding::Positive)
+                    }
+                    Primitive::Circle(center, radius) => builder.add_circle(center, radius, Winding::Positive),
+                    Primitive::Ellipse(center, radii, x_rotation) => {
+                        builder.add_ellipse(center, radii, x_rotation, Winding::Positive)
+                    }
+                }
+                builder.build()
+            };
+
+            let fill = time(&mut || drop(take(tesselate_fill_primitive::<u32>(primitive, fill_copts(), &mut err))));
+            let fill_path = time(&mut || {
+                let mut geometry: VertexBuffers<Vertex, u32> = VertexBuffers::new();
+                fill_into(&path(), &fill_copts(), &mut geometry).unwrap();
+            });
+            let copts = || stroke_copts(2.0, false);
+            let stroke = time(&mut || drop(take(tesselate_stroke_primitive::<u32>(primitive, copts(), &mut err))));
+            let stroke_path = time(&mut || {
+                let mut geometry: VertexBuffers<Vertex, u32> = VertexBuffers::new();
+                stroke_into(&path(), &copts(), &mut geometry).unwrap();
+            });
+
+            println!(
+                "{:<12} fill {:>9.2?} (path {:>9.2?})  stroke {:>9.2?} (path {:>9.2?})",
+                name, fill, fill_path, stroke, stroke_path
+            );
+        }
+    }
+}
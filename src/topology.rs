@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+
+use lyon::tessellation::{GeometryBuilderError, VertexBuffers};
+
+use crate::geometry::GeometryIndex;
+use crate::vertex::Vertex;
+
+pub const TOPOLOGY_INDEXED_TRIANGLE_LIST: i32 = 0;
+pub const TOPOLOGY_TRIANGLE_LIST: i32 = 1;
+pub const TOPOLOGY_TRIANGLE_STRIP: i32 = 2;
+
+// Unknown topologies give indexed triangle lists.
+pub fn topology_or_default(topology: i32) -> i32 {
+    match topology {
+        TOPOLOGY_TRIANGLE_LIST | TOPOLOGY_TRIANGLE_STRIP => topology,
+        _ => TOPOLOGY_INDEXED_TRIANGLE_LIST,
+    }
+}
+
+// Replaces the triangles appended since `first_index` with one vertex per corner, and
+// no indices.
+fn unindex<IndexType: GeometryIndex>(geometry: &mut VertexBuffers<Vertex, IndexType>, first_vertex: usize, first_index: usize) {
+    let corners: Vec<Vertex> = geometry.indices[first_index..]
+        .iter()
+        .map(|i| geometry.vertices[i.to_usize()])
+        .collect();
+
+    geometry.vertices.truncate(first_vertex);
+    geometry.vertices.extend(corners);
+    geometry.indices.truncate(first_index);
+}
+
+// Greedy stripification. A strip grows as long as a triangle that is not used yet shares
+// the last edge with the orientation the strip expects, and each strip keeps the winding
+// of the triangles it is made of.
+fn strips(triangles: &[[usize; 3]]) -> Vec<Vec<usize>> {
+    let mut by_edge: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (i, t) in triangles.iter().enumerate() {
+        for k in 0..3 {
+            by_edge.entry((t[k], t[(k + 1) % 3])).or_default().push(i);
+        }
+    }
+
+    let mut used = vec![false; triangles.len()];
+
+    // The triangle after the strip has the edge (from, to), and its third vertex.
+    let next = |used: &[bool], from: usize, to: usize| -> Option<(usize, usize)> {
+        let candidates = by_edge.get(&(from, to))?;
+        let triangle = *candidates.iter().find(|&&t| !used[t])?;
+        let t = triangles[triangle];
+        let third = t.iter().copied().find(|&v| v != from && v != to)?;
+
+        Some((triangle, third))
+    };
+
+    // Returns the triangles that were added to the strip.
+    let extend = |used: &mut [bool], strip: &mut Vec<usize>| -> Vec<usize> {
+        let mut added = Vec::new();
+        loop {
+            let n = strip.len();
+            // The next triangle is the (n - 2)th, and odd triangles of a strip are flipped by the GPU.
+            let (from, to) = match n % 2 {
+                0 => (strip[n - 2], strip[n - 1]),
+                _ => (strip[n - 1], strip[n - 2]),
+            };
+
+            match next(used, from, to) {
+                Some((triangle, third)) => {
+                    used[triangle] = true;
+                    added.push(triangle);
+                    strip.push(third);
+                }
+                None => break added,
+            }
+        }
+    };
+
+    let mut result = Vec::new();
+    for (i, t) in triangles.iter().enumerate() {
+        if used[i] {
+            continue;
+        }
+
+        // Starts from the rotation of the triangle that gives the longest strip. The triangles
+        // each trial uses are released before the next one.
+        used[i] = true;
+        let mut best: Option<(Vec<usize>, Vec<usize>)> = None;
+        for rotation in 0..3 {
+            let mut strip = vec![t[rotation], t[(rotation + 1) % 3], t[(rotation + 2) % 3]];
+            let added = extend(&mut used, &mut strip);
+            for &triangle in &added {
+                used[triangle] = false;
+            }
+
+            if best.as_ref().is_none_or(|(b, _)| strip.len() > b.len()) {
+                best = Some((strip, added));
+            }
+        }
+
+        let (strip, added) = best.unwrap();
+        for triangle in added {
+            used[triangle] = true;
+        }
+        result.push(strip);
+    }
+
+    result
+}
+
+// Replaces the triangles appended since `first_index` with strips separated by the
+// primitive restart index.
+fn stripify<IndexType: GeometryIndex>(
+    geometry: &mut VertexBuffers<Vertex, IndexType>,
+    first_index: usize,
+) -> Result<(), GeometryBuilderError> {
+    // The restart index can not be used by a vertex.
+    if geometry.vertices.len() > IndexType::RESTART.to_usize() {
+        return Err(GeometryBuilderError::TooManyVertices);
+    }
+
+    let triangles: Vec<[usize; 3]> = geometry.indices[first_index..]
+        .chunks_exact(3)
+        .map(|t| [t[0].to_usize(), t[1].to_usize(), t[2].to_usize()])
+        .collect();
+
+    geometry.indices.truncate(first_index);
+    for strip in strips(&triangles) {
+        if !geometry.indices.is_empty() {
+            geometry.indices.push(IndexType::RESTART);
+        }
+
+        for index in strip {
+            geometry
+                .indices
+                .push(IndexType::from_usize(index).ok_or(GeometryBuilderError::TooManyVertices)?);
+        }
+    }
+
+    Ok(())
+}
+
+// The triangles of strips separated by the restart index. Odd triangles of a strip are flipped,
+// as the GPU does, so that each keeps the winding it was built with.
+pub fn strip_triangles<IndexType: GeometryIndex>(indices: &[IndexType]) -> Vec<[usize; 3]> {
+    let mut triangles = Vec::new();
+    for strip in indices.split(|&i| i == IndexType::RESTART) {
+        for (k, corners) in strip.windows(3).enumerate() {
+            let (a, b, c) = (corners[0].to_usize(), corners[1].to_usize(), corners[2].to_usize());
+            triangles.push(if k % 2 == 0 { [a, b, c] } else { [b, a, c] });
+        }
+    }
+
+    triangles
+}
+
+// Converts the indexed triangle list appended since `first_vertex` and `first_index` to the
+// topology. Other values, including 0, keep the indexed triangle list.
+pub fn convert_topology<IndexType: GeometryIndex>(
+    geometry: &mut VertexBuffers<Vertex, IndexType>,
+    first_vertex: usize,
+    first_index: usize,
+    topology: i32,
+) -> Result<(), GeometryBuilderError> {
+    match topology {
+        TOPOLOGY_TRIANGLE_LIST => {
+            unindex(geometry, first_vertex, first_index);
+            Ok(())
+        }
+        TOPOLOGY_TRIANGLE_STRIP => stripify(geometry, first_index),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A grid of quads, each split in two triangles with the same winding.
+    fn grid(columns: usize, rows: usize) -> VertexBuffers<Vertex, u16> {
+        let mut geometry: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+        for y in 0..=rows {
+            for x in 0..=columns {
+                geometry.vertices.push(Vertex::at(x as f32, y as f32));
+            }
+        }
+
+        let index = |x: usize, y: usize| (y * (columns + 1) + x) as u16;
+        for y in 0..rows {
+            for x in 0..columns {
+                let (a, b, c, d) = (index(x, y), index(x + 1, y), index(x + 1, y + 1), index(x, y + 1));
+                geometry.indices.extend_from_slice(&[a, b, c, a, c, d]);
+            }
+        }
+
+        geometry
+    }
+
+    fn list_triangles(indices: &[u16]) -> Vec<[usize; 3]> {
+        indices
+            .chunks_exact(3)
+            .map(|t| [t[0] as usize, t[1] as usize, t[2] as usize])
+            .collect()
+    }
+
+    // Each triangle rotated to start at its smallest index, which keeps its winding, so that
+    // the same triangles give the same sorted list.
+    fn canonical(triangles: &[[usize; 3]]) -> Vec<[usize; 3]> {
+        let mut result: Vec<[usize; 3]> = triangles
+            .iter()
+            .map(|&t| {
+                let first = (0..3).min_by_key(|&k| t[k]).unwrap();
+                [t[first], t[(first + 1) % 3], t[(first + 2) % 3]]
+            })
+            .collect();
+        result.sort();
+
+        result
+    }
+
+    #[test]
+    fn strips_keep_triangles_and_winding() {
+        let geometry = grid(4, 3);
+        let triangles = list_triangles(&geometry.indices);
+
+        let strips = strips(&triangles);
+        let mut indices: Vec<u16> = Vec::new();
+        for strip in &strips {
+            if !indices.is_empty() {
+                indices.push(u16::RESTART);
+            }
+            indices.extend(strip.iter().map(|&i| i as u16));
+        }
+
+        assert_eq!(canonical(&strip_triangles(&indices)), canonical(&triangles));
+        assert!(strips.len() < triangles.len());
+    }
+
+    #[test]
+    fn stripify_restarts_after_existing_strips() {
+        let mut geometry = grid(3, 2);
+        let first = list_triangles(&geometry.indices);
+        stripify(&mut geometry, 0).unwrap();
+        assert_eq!(canonical(&strip_triangles(&geometry.indices)), canonical(&first));
+
+        // Another grid appended as a list, then converted on its own.
+        let second = grid(2, 2);
+        let (base, first_index) = (geometry.vertices.len(), geometry.indices.len());
+        geometry.vertices.extend_from_slice(&second.vertices);
+        geometry.indices.extend(second.indices.iter().map(|&i| i + base as u16));
+        let appended = list_triangles(&geometry.indices[first_index..]);
+
+        stripify(&mut geometry, first_index).unwrap();
+        assert_eq!(geometry.indices[first_index], u16::RESTART);
+
+        let all: Vec<[usize; 3]> = first.iter().chain(&appended).copied().collect();
+        assert_eq!(canonical(&strip_triangles(&geometry.indices)), canonical(&all));
+    }
+
+    #[test]
+    fn unindex_keeps_corners_in_order() {
+        let mut geometry = grid(2, 2);
+        let original = geometry.clone();
+
+        unindex(&mut geometry, 0, 0);
+        assert!(geometry.indices.is_empty());
+        assert_eq!(geometry.vertices.len(), original.indices.len());
+        for (vertex, &index) in geometry.vertices.iter().zip(&original.indices) {
+            assert_eq!(vertex.position, original.vertices[index as usize].position);
+        }
+    }
+
+    #[test]
+    fn odd_strip_triangles_are_flipped() {
+        let indices: [u16; 7] = [0, 1, 2, 3, u16::RESTART, 4, 5];
+        assert_eq!(strip_triangles(&indices), [[0, 1, 2], [2, 1, 3]]);
+    }
+}
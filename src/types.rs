@@ -1,4 +1,4 @@
-use lyon::path::math::{point, vector};
+use lyon::path::math::{point, vector, Box2D, Transform};
 
 #[derive(Copy, Clone, Debug)]
 #[repr(C)]
@@ -14,6 +14,36 @@ pub struct LyonPoint {
     pub y: f32,
 }
 
+// Elliptical radii for each corner of a rounded rectangle, like CSS `border-radius`.
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct LyonBorderRadii {
+    pub top_left: LyonVector,
+    pub top_right: LyonVector,
+    pub bottom_left: LyonVector,
+    pub bottom_right: LyonVector,
+}
+
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct LyonRect {
+    pub lower_left: [f32; 2],
+    pub upper_right: [f32; 2],
+}
+
+// A 2D affine transform, with the same layout as euclid's `Transform2D`:
+// x' = x * m11 + y * m21 + m31, y' = x * m12 + y * m22 + m32
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct LyonTransform {
+    pub m11: f32,
+    pub m12: f32,
+    pub m21: f32,
+    pub m22: f32,
+    pub m31: f32,
+    pub m32: f32,
+}
+
 impl From<LyonPoint> for lyon::math::Point {
     fn from(p: LyonPoint) -> lyon::math::Point {
         return point(p.x, p.y);
@@ -26,6 +56,21 @@ impl From<LyonVector> for lyon::math::Vector {
     }
 }
 
+impl From<Box2D> for LyonRect {
+    fn from(rect: Box2D) -> LyonRect {
+        return LyonRect {
+            lower_left: rect.min.to_array(),
+            upper_right: rect.max.to_array(),
+        };
+    }
+}
+
+impl From<LyonTransform> for Transform {
+    fn from(t: LyonTransform) -> Transform {
+        return Transform::new(t.m11, t.m12, t.m21, t.m22, t.m31, t.m32);
+    }
+}
+
 use lyon::path::builder::WithSvg;
 use lyon::path::path::BuilderImpl;
 
@@ -17,4 +17,29 @@ pub struct Vertex {
 
     // Index of the shape
     pub shape_ind: i32,
+
+    // Strokes only: signed distance from the centerline, positive on the
+    // positive side of the path. Interpolates linearly across the stroke.
+    pub centerline_distance: f32,
+
+    // Strokes only: distance along the path from the start of the sub-path.
+    pub path_distance: f32,
+}
+
+#[cfg(test)]
+impl Vertex {
+    // A filled vertex with only a position.
+    pub fn at(x: f32, y: f32) -> Vertex {
+        Vertex {
+            position: [x, y],
+            original_position: [x, y],
+            normal: [0.0, 0.0],
+            color: 0,
+            primitive_type: PRIMITIVE_TYPE_FILLED,
+            fill_ind: 0,
+            shape_ind: 0,
+            centerline_distance: 0.0,
+            path_distance: 0.0,
+        }
+    }
 }
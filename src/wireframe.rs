@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use crate::geometry::{triangles, Geometry, GeometryIndex};
+use crate::vertex::Vertex;
+
+// Vertices are compared by position, so that edges are shared even between triangles
+// that do not share vertices, as in unindexed lists.
+fn position_key(vertex: &Vertex) -> (u32, u32) {
+    (vertex.position[0].to_bits(), vertex.position[1].to_bits())
+}
+
+// An edge, by the sorted position keys of its ends.
+type EdgeKey = ((u32, u32), (u32, u32));
+
+// Pairs of indices, one per unique edge in the order they are first met. With `outline_only`,
+// only the edges that belong to a single triangle are kept. None if a vertex of an unindexed
+// geometry has no index of this size.
+pub fn wireframe<IndexType: GeometryIndex>(
+    geometry: &Geometry<IndexType>,
+    outline_only: bool,
+) -> Option<Vec<IndexType>> {
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    let mut uses: HashMap<EdgeKey, (usize, u32)> = HashMap::new();
+
+    let vertices = &geometry.buffers.vertices;
+    for triangle in triangles(geometry) {
+        for k in 0..3 {
+            let (a, b) = (triangle[k], triangle[(k + 1) % 3]);
+            let (key_a, key_b) = (position_key(&vertices[a]), position_key(&vertices[b]));
+            if key_a == key_b {
+                continue;
+            }
+
+            let key = if key_a < key_b { (key_a, key_b) } else { (key_b, key_a) };
+            let entry = uses.entry(key).or_insert_with(|| {
+                edges.push((a, b));
+                (edges.len() - 1, 0)
+            });
+            entry.1 += 1;
+        }
+    }
+
+    let mut keep = vec![true; edges.len()];
+    if outline_only {
+        for (edge, count) in uses.values() {
+            keep[*edge] = *count == 1;
+        }
+    }
+
+    edges
+        .into_iter()
+        .zip(keep)
+        .filter(|(_, keep)| *keep)
+        .flat_map(|((a, b), _)| [a, b])
+        .map(IndexType::from_usize)
+        .collect()
+}
+
+#[no_mangle]
+pub extern fn LyonGeometry16_Wireframe(p: *mut Geometry<u16>, outline_only: i32) -> *mut Vec<u16> {
+    assert!(!p.is_null());
+    let geometry = unsafe { &*p };
+
+    match wireframe(geometry, outline_only != 0) {
+        Some(indices) => Box::into_raw(Box::new(indices)),
+        None => std::ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub extern fn LyonGeometry32_Wireframe(p: *mut Geometry<u32>, outline_only: i32) -> *mut Vec<u32> {
+    assert!(!p.is_null());
+    let geometry = unsafe { &*p };
+
+    match wireframe(geometry, outline_only != 0) {
+        Some(indices) => Box::into_raw(Box::new(indices)),
+        None => std::ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub extern fn LyonWireframe16_IndicesLength(p: *mut Vec<u16>) -> u32 {
+    unsafe { (*p).len() as u32 }
+}
+
+#[no_mangle]
+pub extern fn LyonWireframe16_IndicesData(p: *mut Vec<u16>) -> *const u16 {
+    unsafe { (*p).as_ptr() }
+}
+
+#[no_mangle]
+pub extern fn LyonFreeWireframe16(p: *mut Vec<u16>) {
+    unsafe { Box::from_raw(p) };
+}
+
+#[no_mangle]
+pub extern fn LyonWireframe32_IndicesLength(p: *mut Vec<u32>) -> u32 {
+    unsafe { (*p).len() as u32 }
+}
+
+#[no_mangle]
+pub extern fn LyonWireframe32_IndicesData(p: *mut Vec<u32>) -> *const u32 {
+    unsafe { (*p).as_ptr() }
+}
+
+#[no_mangle]
+pub extern fn LyonFreeWireframe32(p: *mut Vec<u32>) {
+    unsafe { Box::from_raw(p) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::{
+        convert_topology, TOPOLOGY_INDEXED_TRIANGLE_LIST, TOPOLOGY_TRIANGLE_LIST, TOPOLOGY_TRIANGLE_STRIP,
+    };
+    use lyon::tessellation::VertexBuffers;
+
+    // Two unit squares side by side, each split in two triangles.
+    fn squares(topology: i32) -> Geometry<u16> {
+        let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+        for y in 0..2 {
+            for x in 0..3 {
+                buffers.vertices.push(Vertex::at(x as f32, y as f32));
+            }
+        }
+        buffers.indices = vec![0, 1, 4, 0, 4, 3, 1, 2, 5, 1, 5, 4];
+        convert_topology(&mut buffers, 0, 0, topology).unwrap();
+
+        Geometry { buffers, topology }
+    }
+
+    // The edges by the positions of their ends, sorted.
+    fn edges(geometry: &Geometry<u16>, indices: &[u16]) -> Vec<[(i32, i32); 2]> {
+        let position = |i: u16| {
+            let p = geometry.buffers.vertices[i as usize].position;
+            (p[0] as i32, p[1] as i32)
+        };
+
+        let mut edges: Vec<[(i32, i32); 2]> = indices
+            .chunks_exact(2)
+            .map(|e| {
+                let mut edge = [position(e[0]), position(e[1])];
+                edge.sort();
+                edge
+            })
+            .collect();
+        edges.sort();
+
+        edges
+    }
+
+    #[test]
+    fn every_topology_gives_the_same_edges() {
+        let outline = vec![
+            [(0, 0), (0, 1)],
+            [(0, 0), (1, 0)],
+            [(0, 1), (1, 1)],
+            [(1, 0), (2, 0)],
+            [(1, 1), (2, 1)],
+            [(2, 0), (2, 1)],
+        ];
+        let mut all = outline.clone();
+        all.extend_from_slice(&[[(0, 0), (1, 1)], [(1, 0), (1, 1)], [(1, 0), (2, 1)]]);
+        all.sort();
+
+        for topology in [TOPOLOGY_INDEXED_TRIANGLE_LIST, TOPOLOGY_TRIANGLE_LIST, TOPOLOGY_TRIANGLE_STRIP] {
+            let geometry = squares(topology);
+            assert_eq!(edges(&geometry, &wireframe(&geometry, false).unwrap()), all);
+            assert_eq!(edges(&geometry, &wireframe(&geometry, true).unwrap()), outline);
+        }
+    }
+
+    #[test]
+    fn unindexed_indices_that_do_not_fit() {
+        let geometry: Geometry<u16> = Geometry {
+            buffers: VertexBuffers {
+                vertices: (0..u16::MAX as usize + 3).map(|i| Vertex::at(i as f32, (i % 3) as f32)).collect(),
+                indices: Vec::new(),
+            },
+            topology: TOPOLOGY_TRIANGLE_LIST,
+        };
+
+        assert!(wireframe(&geometry, false).is_none());
+    }
+}